@@ -0,0 +1,175 @@
+; HEADER_BLOCK_START
+; BambuStudio 01.07.04.52
+; model printing time: 4m 12s; total estimated time: 10m 3s
+; total layer number: 3
+; model label id: 82
+; HEADER_BLOCK_END
+
+; EXECUTABLE_BLOCK_START
+M73 P0 R10
+G28
+G90
+M83
+M104 S210
+M190 S60
+M109 S210
+G92 E0
+M204 S5000
+SET_VELOCITY_LIMIT ACCEL=5000 ACCEL_TO_DECEL=2500
+; CHANGE_LAYER
+; Z_HEIGHT: 0.2
+; LAYER_HEIGHT: 0.2
+G1 Z0.200 F720
+G1 E-.8 F2100
+G1 X95.000 Y95.000 F12000
+G1 E.8 F2100
+; FEATURE: Skirt
+G1 F1800
+G1 X125.000 Y95.000 E.66214
+G1 X125.000 Y125.000 E.66214
+G1 X95.000 Y125.000 E.66214
+G1 X95.000 Y95.000 E.66214
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; FEATURE: Inner wall
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; FEATURE: Outer wall
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; FEATURE: Bottom surface
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+; CHANGE_LAYER
+; Z_HEIGHT: 0.4
+; LAYER_HEIGHT: 0.2
+G1 Z0.400 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; FEATURE: Inner wall
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; FEATURE: Outer wall
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; FEATURE: Sparse infill
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+G1 E-.8 F2100
+G1 X121.000 Y105.000 F12000
+G1 E.8 F2100
+; FEATURE: Support
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+; FEATURE: Support interface
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+G1 E-.8 F2100
+G1 X110.000 Y110.000 F12000
+G1 E.8 F2100
+; FEATURE: Gap infill
+G1 X111.000 Y111.000 E.02
+; CHANGE_LAYER
+; Z_HEIGHT: 0.6
+; LAYER_HEIGHT: 0.2
+G1 Z0.600 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; FEATURE: Inner wall
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; FEATURE: Outer wall
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; FEATURE: Top surface
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+; EXECUTABLE_BLOCK_END
+; filament used [mm] = 412.56
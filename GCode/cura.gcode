@@ -0,0 +1,202 @@
+;FLAVOR:Marlin
+;TIME:312
+;Filament used: 0.412m
+;Layer height: 0.2
+;MINX:100.2
+;MINY:100.2
+;MINZ:0.2
+;MAXX:119.8
+;MAXY:119.8
+;MAXZ:0.6
+;Generated with Cura_SteamEngine 5.4.0
+M140 S60
+M105
+M190 S60
+M104 S210
+M105
+M109 S210
+M82 ;absolute extrusion mode
+G28 ;Home
+G92 E0
+G1 Z2.0 F3000
+G1 X0.1 Y20 Z0.3 F5000.0
+G1 X0.1 Y200.0 Z0.3 F1500.0 E15
+G92 E0
+G1 Z2.0 F3000
+M82 ;absolute extrusion mode
+G92 E0
+G92 E0
+G1 F2700 E-5
+;LAYER_COUNT:3
+;LAYER:0
+M107
+G0 F3600 X95.000 Y95.000 Z0.2
+;TYPE:SKIRT
+G1 F2700 E0.00000
+G1 F1800
+G1 X125.000 Y95.000 E0.66000
+G1 X125.000 Y125.000 E1.32000
+G1 X95.000 Y125.000 E1.98000
+G1 X95.000 Y95.000 E2.64000
+;MESH:cube.stl
+G1 F2700 E-2.36000
+G0 F6000 X101.000 Y101.000
+G1 F2700 E2.64000
+;TYPE:WALL-INNER
+G1 F1800
+G1 X119.000 Y101.000 E3.30000
+G1 X119.000 Y119.000 E3.96000
+G1 X101.000 Y119.000 E4.62000
+G1 X101.000 Y101.000 E5.28000
+G1 F2700 E0.28000
+G0 F6000 X100.200 Y100.200
+G1 F2700 E5.28000
+;TYPE:WALL-OUTER
+G1 F1500
+G1 X119.800 Y100.200 E5.94000
+G1 X119.800 Y119.800 E6.60000
+G1 X100.200 Y119.800 E7.26000
+G1 X100.200 Y100.200 E7.92000
+G1 F2700 E2.92000
+G0 F6000 X102.000 Y102.000
+G1 F2700 E7.92000
+;TYPE:SKIN
+G1 X118.000 Y102.000 E8.42000
+G0 F6000 X118.000 Y104.000
+G1 F1800 X102.000 Y104.000 E8.92000
+G0 F6000 X102.000 Y106.000
+G1 X118.000 Y106.000 E9.42000
+G0 F6000 X118.000 Y108.000
+G1 F1800 X102.000 Y108.000 E9.92000
+G0 F6000 X102.000 Y110.000
+G1 X118.000 Y110.000 E10.42000
+G0 F6000 X118.000 Y112.000
+G1 F1800 X102.000 Y112.000 E10.92000
+G0 F6000 X102.000 Y114.000
+G1 X118.000 Y114.000 E11.42000
+G0 F6000 X118.000 Y116.000
+G1 F1800 X102.000 Y116.000 E11.92000
+G0 F6000 X102.000 Y118.000
+;MESH:NONMESH
+;TIME_ELAPSED:100.0
+;LAYER:1
+M107
+G0 F3600 X101.000 Y101.000 Z0.4
+;MESH:cube.stl
+G1 F2700 E6.92000
+G0 F6000 X101.000 Y101.000
+G1 F2700 E11.92000
+;TYPE:WALL-INNER
+G1 F1800
+G1 X119.000 Y101.000 E12.58000
+G1 X119.000 Y119.000 E13.24000
+G1 X101.000 Y119.000 E13.90000
+G1 X101.000 Y101.000 E14.56000
+G1 F2700 E9.56000
+G0 F6000 X100.200 Y100.200
+G1 F2700 E14.56000
+;TYPE:WALL-OUTER
+G1 F1500
+G1 X119.800 Y100.200 E15.22000
+G1 X119.800 Y119.800 E15.88000
+G1 X100.200 Y119.800 E16.54000
+G1 X100.200 Y100.200 E17.20000
+G1 F2700 E12.20000
+G0 F6000 X102.000 Y102.000
+G1 F2700 E17.20000
+;TYPE:FILL
+G1 X118.000 Y102.000 E17.70000
+G0 F6000 X118.000 Y104.000
+G1 F1800 X102.000 Y104.000 E18.20000
+G0 F6000 X102.000 Y106.000
+G1 X118.000 Y106.000 E18.70000
+G0 F6000 X118.000 Y108.000
+G1 F1800 X102.000 Y108.000 E19.20000
+G0 F6000 X102.000 Y110.000
+G1 X118.000 Y110.000 E19.70000
+G0 F6000 X118.000 Y112.000
+G1 F1800 X102.000 Y112.000 E20.20000
+G0 F6000 X102.000 Y114.000
+G1 X118.000 Y114.000 E20.70000
+G0 F6000 X118.000 Y116.000
+G1 F1800 X102.000 Y116.000 E21.20000
+G0 F6000 X102.000 Y118.000
+G1 F2700 E16.20000
+G0 F6000 X121.000 Y105.000
+G1 F2700 E21.20000
+;TYPE:SUPPORT
+G1 F2400
+G1 X123.000 Y105.000 E21.86000
+G1 X123.000 Y107.000 E22.52000
+G1 X121.000 Y107.000 E23.18000
+G1 X121.000 Y105.000 E23.84000
+;TYPE:SUPPORT-INTERFACE
+G1 F2400
+G1 X123.000 Y105.000 E24.50000
+G1 X123.000 Y107.000 E25.16000
+G1 X121.000 Y107.000 E25.82000
+G1 X121.000 Y105.000 E26.48000
+;MESH:NONMESH
+;TIME_ELAPSED:200.0
+;LAYER:2
+M107
+G0 F3600 X101.000 Y101.000 Z0.6
+;MESH:cube.stl
+G1 F2700 E21.48000
+G0 F6000 X101.000 Y101.000
+G1 F2700 E26.48000
+;TYPE:WALL-INNER
+G1 F1800
+G1 X119.000 Y101.000 E27.14000
+G1 X119.000 Y119.000 E27.80000
+G1 X101.000 Y119.000 E28.46000
+G1 X101.000 Y101.000 E29.12000
+G1 F2700 E24.12000
+G0 F6000 X100.200 Y100.200
+G1 F2700 E29.12000
+;TYPE:WALL-OUTER
+G1 F1500
+G1 X119.800 Y100.200 E29.78000
+G1 X119.800 Y119.800 E30.44000
+G1 X100.200 Y119.800 E31.10000
+G1 X100.200 Y100.200 E31.76000
+G1 F2700 E26.76000
+G0 F6000 X102.000 Y102.000
+G1 F2700 E31.76000
+;TYPE:SKIN
+G1 X118.000 Y102.000 E32.26000
+G0 F6000 X118.000 Y104.000
+G1 F1800 X102.000 Y104.000 E32.76000
+G0 F6000 X102.000 Y106.000
+G1 X118.000 Y106.000 E33.26000
+G0 F6000 X118.000 Y108.000
+G1 F1800 X102.000 Y108.000 E33.76000
+G0 F6000 X102.000 Y110.000
+G1 X118.000 Y110.000 E34.26000
+G0 F6000 X118.000 Y112.000
+G1 F1800 X102.000 Y112.000 E34.76000
+G0 F6000 X102.000 Y114.000
+G1 X118.000 Y114.000 E35.26000
+G0 F6000 X118.000 Y116.000
+G1 F1800 X102.000 Y116.000 E35.76000
+G0 F6000 X102.000 Y118.000
+;MESH:NONMESH
+;TIME_ELAPSED:300.0
+G1 F2700 E30.76000
+M140 S0
+M107
+G91
+G1 E-2 F2700
+G1 E-2 Z0.2 F2400
+G1 X5 Y5 F3000
+G1 Z10
+G90
+G1 X0 Y235
+M106 S0
+M104 S0
+M140 S0
+M84 X Y E
+M82 ;absolute extrusion mode
+M104 S0
+;End of Gcode
+;SETTING_3 {"global_quality": "[general]\\nversion = 4\\nname = Standard Quality"}
@@ -0,0 +1,82 @@
+; HEADER_BLOCK_START
+; generated by OrcaSlicer 2.1.1 on 2024-06-02 at 14:22:10
+; total layer number: 2
+; HEADER_BLOCK_END
+
+; EXECUTABLE_BLOCK_START
+M73 P0 R1
+M106 S0
+M106 P2 S0
+;TYPE:Custom
+PRINT_START EXTRUDER=215 BED=60
+G90
+M83
+G92 E0
+G1 Z.2 F720
+;LAYER_CHANGE
+;Z:0.2
+;HEIGHT:0.2
+G1 X95.123 Y95.456 F30000
+G1 Z.2
+G1 E.8 F2100
+;TYPE:Brim
+G1 F1800
+G1 X105.01 Y95.456 E.3671
+G1 X105.01 Y105.01 E.3547
+G1 X95.123 Y105.01 E.3671
+G1 X95.123 Y95.456 E.3547
+G1 X94.723 Y95.056 F30000
+G1 F1800
+G1 X105.41 Y95.056 E.3968
+G1 X105.41 Y105.41 E.3844
+G1 X94.723 Y105.41 E.3968
+G1 X94.723 Y95.056 E.3844
+;TYPE:Prime tower
+G1 X150.2 Y150.2 F30000
+G1 F3000
+G1 X165.2 Y150.2 E.5569
+G1 X165.2 Y152.2 E.0743
+G1 X150.2 Y152.2 E.5569
+G1 X150.2 Y154.2 F30000
+G1 F3000
+G1 X165.2 Y154.2 E.5569
+;TYPE:Support transition
+G1 X100.4 Y100.4 F30000
+G1 F3000
+G1 X102.8 Y100.4 E.0891
+G1 X102.8 Y102.8 E.0891
+G1 X100.4 Y103.6 F30000
+G1 F3000
+G1 X102.8 Y103.6 E.0891
+;LAYER_CHANGE
+;Z:0.4
+;HEIGHT:0.2
+G1 E-.8 F2100
+G1 Z.4 F720
+G1 X96.1 Y96.1 F30000
+G1 E.8 F2100
+;TYPE:Floating vertical shell
+G1 F3600
+G1 X104.1 Y96.1 E.297
+G1 X104.1 Y104.1 E.297
+G1 X98.1 Y98.1 F30000
+G1 F3600
+G1 X102.1 Y98.1 E.1485
+;TYPE:Internal Bridge
+G1 X97.2 Y99.2 F30000
+G1 F1200
+G1 X103.2 Y99.2 E.2784
+G1 X97.2 Y100.8 F30000
+G1 F1200
+G1 X103.2 Y100.8 E.2784
+;TYPE:Ironing
+G1 X97.5 Y97.5 F30000
+G1 F1800
+G1 X102.5 Y97.5 E.0093
+G1 X102.5 Y97.9 F30000
+G1 F1800
+G1 X97.5 Y97.9 E.0093
+;TYPE:Custom
+; filament end gcode
+PRINT_END
+; EXECUTABLE_BLOCK_END
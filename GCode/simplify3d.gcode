@@ -0,0 +1,177 @@
+; G-Code generated by Simplify3D(R) Version 4.1.2
+; Jul 9, 2023 at 11:51:00 AM
+; Settings Summary
+;   processName,Process1
+;   applyToModels,cube
+;   profileName,Generic (modified)
+;   layerHeight,0.2
+;   topSolidLayers,3
+;   bottomSolidLayers,3
+;   perimeterOutlines,2
+; Build Summary
+;   Build time: 0 hours 4 minutes
+;   Filament length: 412.6 mm (0.41 m)
+G28
+G90
+M83
+M104 S210
+M190 S60
+M109 S210
+G92 E0
+M204 S5000
+SET_VELOCITY_LIMIT ACCEL=5000 ACCEL_TO_DECEL=2500
+; layer 1, Z = 0.200
+G1 Z0.200 F720
+G1 E-.8 F2100
+G1 X95.000 Y95.000 F12000
+G1 E.8 F2100
+; feature skirt
+G1 F1800
+G1 X125.000 Y95.000 E.66214
+G1 X125.000 Y125.000 E.66214
+G1 X95.000 Y125.000 E.66214
+G1 X95.000 Y95.000 E.66214
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; feature inner perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; feature outer perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; feature solid layer
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+; layer 2, Z = 0.400
+G1 Z0.400 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; feature inner perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; feature outer perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; feature infill
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+G1 E-.8 F2100
+G1 X121.000 Y105.000 F12000
+G1 E.8 F2100
+; feature support
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+; feature dense support
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+G1 E-.8 F2100
+G1 X110.000 Y110.000 F12000
+G1 E.8 F2100
+; feature gap fill
+G1 X111.000 Y111.000 E.02
+; layer 3, Z = 0.600
+G1 Z0.600 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+; feature inner perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+; feature outer perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+; feature solid layer
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+; layer end
+M104 S0
+M140 S0
+G28 X0
+M84
+; Build Summary
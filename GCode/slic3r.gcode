@@ -0,0 +1,170 @@
+; generated by Slic3r 1.3.0 on 2023-07-09 at 11:51:00
+
+; external perimeters extrusion width = 0.45mm
+; perimeters extrusion width = 0.45mm
+; infill extrusion width = 0.45mm
+
+M107
+G28 ; home all axes
+G1 Z5 F5000 ; lift nozzle
+G28
+G90
+M83
+M104 S210
+M190 S60
+M109 S210
+G92 E0
+M204 S5000
+SET_VELOCITY_LIMIT ACCEL=5000 ACCEL_TO_DECEL=2500
+G1 Z0.200 F720
+G1 E-.8 F2100
+G1 X95.000 Y95.000 F12000
+G1 E.8 F2100
+;TYPE:Skirt
+G1 F1800
+G1 X125.000 Y95.000 E.66214
+G1 X125.000 Y125.000 E.66214
+G1 X95.000 Y125.000 E.66214
+G1 X95.000 Y95.000 E.66214
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+;TYPE:Perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+;TYPE:External perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+;TYPE:Solid infill
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+G1 Z0.400 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+;TYPE:Perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+;TYPE:External perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+;TYPE:Internal infill
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+G1 E-.8 F2100
+G1 X121.000 Y105.000 F12000
+G1 E.8 F2100
+;TYPE:Support material
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+;TYPE:Support material interface
+G1 F3000
+G1 X123.000 Y105.000 E.66214
+G1 X123.000 Y107.000 E.66214
+G1 X121.000 Y107.000 E.66214
+G1 X121.000 Y105.000 E.66214
+G1 E-.8 F2100
+G1 X110.000 Y110.000 F12000
+G1 E.8 F2100
+;TYPE:Gap fill
+G1 X111.000 Y111.000 E.02
+G1 Z0.600 F720
+G1 E-.8 F2100
+G1 X101.000 Y101.000 F12000
+G1 E.8 F2100
+;TYPE:Perimeter
+G1 F3000
+G1 X119.000 Y101.000 E.66214
+G1 X119.000 Y119.000 E.66214
+G1 X101.000 Y119.000 E.66214
+G1 X101.000 Y101.000 E.66214
+G1 E-.8 F2100
+G1 X100.200 Y100.200 F12000
+G1 E.8 F2100
+;TYPE:External perimeter
+G1 F2400
+G1 X119.800 Y100.200 E.66214
+G1 X119.800 Y119.800 E.66214
+G1 X100.200 Y119.800 E.66214
+G1 X100.200 Y100.200 E.66214
+G1 E-.8 F2100
+G1 X102.000 Y102.000 F12000
+G1 E.8 F2100
+;TYPE:Top solid infill
+G1 X118.000 Y102.000 E.55213
+G1 X118.000 Y104.000 F12000
+G1 X102.000 Y104.000 E.55213
+G1 X102.000 Y106.000 F12000
+G1 X118.000 Y106.000 E.55213
+G1 X118.000 Y108.000 F12000
+G1 X102.000 Y108.000 E.55213
+G1 X102.000 Y110.000 F12000
+G1 X118.000 Y110.000 E.55213
+G1 X118.000 Y112.000 F12000
+G1 X102.000 Y112.000 E.55213
+G1 X102.000 Y114.000 F12000
+G1 X118.000 Y114.000 E.55213
+G1 X118.000 Y116.000 F12000
+G1 X102.000 Y116.000 E.55213
+G1 X102.000 Y118.000 F12000
+M107
+M104 S0 ; turn off temperature
+G28 X0  ; home X axis
+M84     ; disable motors
+
+; filament used = 412.6mm (1.0cm3)
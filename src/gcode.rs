@@ -1,44 +1,585 @@
-use crate::types::{AccelerationControl, AccelerationSettings, FeatureType};
+use crate::types::{AccelerationControl, Config, FeatureType, Firmware, Register, Version};
 use counter::Counter;
 use generator::{done, Generator, Gn};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// G-Code command parsed from a line, e.g. `G1` with its parameters `X10.5 Y-3 E.2`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Command {
+    /// Normalized command code, e.g. `G1` for both `G1` and `G01`
+    pub(crate) code: String,
+    /// Parameter letters and their values in the order they appear
+    pub(crate) params: Vec<(char, f64)>,
+}
+
+impl Command {
+    /// Parses a line into its command and parameter words. Comments, line numbers and
+    /// checksums are skipped. Returns `None` for lines without a `G`, `M` or `T` command,
+    /// e.g. comments or extended commands like `SET_VELOCITY_LIMIT ACCEL=1000`.
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let line = line.split(';').next()?;
+        let line = line.split('*').next()?;
+
+        let mut words = Words {
+            chars: line.chars().peekable(),
+        };
+        let (mut letter, mut value) = words.next()?.ok()?;
+        if letter == 'N' {
+            (letter, value) = words.next()?.ok()?;
+        }
+        if !matches!(letter, 'G' | 'M' | 'T') {
+            return None;
+        }
+
+        Some(Self {
+            code: format!("{letter}{value}"),
+            params: words.collect::<Result<_, _>>().ok()?,
+        })
+    }
+
+    /// Returns the value of a parameter, e.g. `0.35` for `Z` in `G1 Z0.35 F7800`
+    pub(crate) fn param(&self, letter: char) -> Option<f64> {
+        self.params
+            .iter()
+            .find(|(l, _)| *l == letter)
+            .map(|(_, value)| *value)
+    }
+
+    /// Checks if the command is a linear or arc move
+    pub(crate) fn is_move(&self) -> bool {
+        matches!(self.code.as_str(), "G0" | "G1" | "G2" | "G3")
+    }
+
+    /// Checks if the command is a clockwise or counter-clockwise arc move
+    pub(crate) fn is_arc(&self) -> bool {
+        matches!(self.code.as_str(), "G2" | "G3")
+    }
+}
+
+/// Position changes below this are rounding errors of relative moves
+const POSITION_TOLERANCE: f64 = 1e-6;
+
+/// Kind of a move, classified by the change of the position and the extruder position
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MoveKind {
+    /// Move in the XY plane without extrusion
+    Travel,
+    /// Move in the XY plane extruding filament
+    Extrusion,
+    /// Filament retracted or primed without moving, including firmware retraction
+    Retraction,
+    /// Z move without extrusion, e.g. lifting the nozzle for a travel move
+    ZHop,
+    /// Move in the XY plane retracting filament
+    Wipe,
+}
+
+impl MoveKind {
+    /// Checks if the move is part of a printed feature, other moves in between travel moves
+    /// belong to the travel, e.g. retracting and lifting the nozzle before it.
+    pub(crate) fn is_printing(&self) -> bool {
+        matches!(self, MoveKind::Extrusion | MoveKind::Wipe)
+    }
+}
+
+/// Modal state of the machine, tracked to classify moves
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct MachineState {
+    /// Coordinates of moves are absolute (`G90`) or relative (`G91`)
+    pub(crate) absolute_xyz: bool,
+    /// Extruder positions are absolute (`M82`) or relative (`M83`), relative coordinates
+    /// (`G91`) make them relative too without changing this mode
+    pub(crate) absolute_e: bool,
+    /// Current X, Y and Z position
+    pub(crate) position: [f64; 3],
+    /// Current extruder position
+    pub(crate) extruder: f64,
+    /// Feedrate of moves in mm/min, set by the last move with an `F` parameter
+    pub(crate) feedrate: Option<f64>,
+}
+
+impl Default for MachineState {
+    fn default() -> Self {
+        Self {
+            absolute_xyz: true,
+            absolute_e: true,
+            position: [0.0; 3],
+            extruder: 0.0,
+            feedrate: None,
+        }
+    }
+}
+
+impl MachineState {
+    /// Updates the state with a command, returns the kind of move for commands that move
+    /// the toolhead or the extruder.
+    pub(crate) fn update(&mut self, command: &Command) -> Option<MoveKind> {
+        match command.code.as_str() {
+            "G90" => self.absolute_xyz = true,
+            "G91" => self.absolute_xyz = false,
+            "M82" => self.absolute_e = true,
+            "M83" => self.absolute_e = false,
+            "G92" if command.params.is_empty() => {
+                self.position = [0.0; 3];
+                self.extruder = 0.0;
+            }
+            "G92" => {
+                for (letter, value) in &command.params {
+                    match letter {
+                        'X' => self.position[0] = *value,
+                        'Y' => self.position[1] = *value,
+                        'Z' => self.position[2] = *value,
+                        'E' => self.extruder = *value,
+                        _ => {}
+                    }
+                }
+            }
+            // Firmware retraction, G10 with parameters sets offsets or temperatures
+            "G10" | "G11" if command.param('P').is_none() && command.param('L').is_none() => {
+                return Some(MoveKind::Retraction)
+            }
+            _ if command.is_move() => return self.apply_move(command),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Speed of moves in mm/s
+    pub(crate) fn speed(&self) -> Option<f64> {
+        self.feedrate.map(|feedrate| feedrate / 60.0)
+    }
+
+    /// Distance in the XY plane from a previous position to the current position
+    pub(crate) fn xy_distance(&self, from: [f64; 3]) -> f64 {
+        (self.position[0] - from[0]).hypot(self.position[1] - from[1])
+    }
+
+    /// Length of the path in the XY plane from a previous position to the current position,
+    /// following the arc of `G2`/`G3` moves. Arcs ending where they started are full circles.
+    pub(crate) fn xy_path_length(&self, from: [f64; 3], command: &Command) -> f64 {
+        let chord = self.xy_distance(from);
+        if !command.is_arc() {
+            return chord;
+        }
+
+        let (radius, sweep) = match command.param('R') {
+            // Negative radii select the arc longer than a half circle
+            Some(r) if r.abs() > POSITION_TOLERANCE => {
+                let sweep = 2.0 * (chord / (2.0 * r.abs())).min(1.0).asin();
+                match r < 0.0 {
+                    true => (r.abs(), 2.0 * PI - sweep),
+                    false => (r, sweep),
+                }
+            }
+            _ => {
+                let center = [
+                    from[0] + command.param('I').unwrap_or(0.0),
+                    from[1] + command.param('J').unwrap_or(0.0),
+                ];
+                let start = (from[1] - center[1]).atan2(from[0] - center[0]);
+                let end = (self.position[1] - center[1]).atan2(self.position[0] - center[0]);
+                let sweep = match command.code.as_str() {
+                    "G2" => start - end,
+                    _ => end - start,
+                }
+                .rem_euclid(2.0 * PI);
+                let sweep = match chord > POSITION_TOLERANCE {
+                    true => sweep,
+                    false => 2.0 * PI,
+                };
+                ((from[0] - center[0]).hypot(from[1] - center[1]), sweep)
+            }
+        };
+
+        radius * sweep
+    }
+
+    fn apply_move(&mut self, command: &Command) -> Option<MoveKind> {
+        if let Some(feedrate) = command.param('F') {
+            self.feedrate = Some(feedrate);
+        }
+
+        let mut xy_moved = false;
+        let mut z_moved = false;
+        for (axis, letter) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            if let Some(value) = command.param(letter) {
+                let target = match self.absolute_xyz {
+                    true => value,
+                    false => self.position[axis] + value,
+                };
+                if (target - self.position[axis]).abs() > POSITION_TOLERANCE {
+                    xy_moved |= axis < 2;
+                    z_moved |= axis == 2;
+                }
+                self.position[axis] = target;
+            }
+        }
+
+        // Arcs move in the XY plane even if they end where they started, e.g. full circles
+        xy_moved |= command.is_arc()
+            && ['I', 'J', 'R'].into_iter().any(|letter| {
+                command
+                    .param(letter)
+                    .is_some_and(|v| v.abs() > POSITION_TOLERANCE)
+            });
+
+        let extruded =
+            command
+                .param('E')
+                .map_or(0.0, |e| match self.absolute_xyz && self.absolute_e {
+                    true => e - self.extruder,
+                    false => e,
+                });
+        self.extruder += extruded;
+
+        match (xy_moved, z_moved) {
+            (true, _) if extruded > POSITION_TOLERANCE => Some(MoveKind::Extrusion),
+            (true, _) if extruded < -POSITION_TOLERANCE => Some(MoveKind::Wipe),
+            (true, _) => Some(MoveKind::Travel),
+            (false, _) if extruded.abs() > POSITION_TOLERANCE => Some(MoveKind::Retraction),
+            (false, true) => Some(MoveKind::ZHop),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Iterator over the words of a line, a letter followed by a number
+struct Words<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Iterator for Words<'_> {
+    type Item = Result<(char, f64), ()>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Parenthesized comments may appear between words
+        loop {
+            match self.chars.peek()? {
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                '(' => {
+                    self.chars.find(|c| *c == ')');
+                }
+                _ => break,
+            }
+        }
+
+        let letter = self.chars.next()?.to_ascii_uppercase();
+        if !letter.is_ascii_alphabetic() {
+            return Some(Err(()));
+        }
+
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+        {
+            number.push(c);
+        }
+
+        // Parameters without a value, e.g. `G28 X`
+        if number.is_empty() {
+            return match self.chars.peek() {
+                None => Some(Ok((letter, 0.0))),
+                Some(c) if c.is_whitespace() => Some(Ok((letter, 0.0))),
+                Some(_) => Some(Err(())),
+            };
+        }
+
+        Some(number.parse().map(|value| (letter, value)).map_err(|_| ()))
+    }
+}
+
+/// Acceleration limits of the printer. Requested changes are emitted right before the next
+/// move, and only the commands changing a setting that doesn't have the value already.
+#[derive(Default)]
+pub(crate) struct VelocityLimits {
+    /// Firmware the commands are generated for
+    firmware: Firmware,
+    /// Last command written to each firmware setting
+    active: HashMap<Register, String>,
+    pending: Option<(FeatureType, AccelerationControl)>,
+    /// Most recently requested limits
+    requested: Option<(FeatureType, AccelerationControl)>,
+    /// Number of emitted changes per feature type
+    stats: Counter<FeatureType, u64>,
+    /// Number of requested changes that were redundant or replaced before the next move
+    skipped: u64,
+}
+
+impl VelocityLimits {
+    pub(crate) fn new(firmware: Firmware) -> Self {
+        Self {
+            firmware,
+            ..Default::default()
+        }
+    }
+
+    /// Requests the limits for a feature type, replacing an earlier request without motion
+    /// in between
+    pub(crate) fn set(&mut self, feature_type: &FeatureType, control: &AccelerationControl) {
+        if self.pending.replace((*feature_type, *control)).is_some() {
+            self.skipped += 1;
+        }
+        self.requested = Some((*feature_type, *control));
+    }
+
+    /// Most recently requested limits and their feature type, whether emitted or not
+    pub(crate) fn requested(&self) -> Option<(FeatureType, AccelerationControl)> {
+        self.requested
+    }
+
+    /// Emits the requested limits before a move, skipping the commands for settings that
+    /// are already active
+    pub(crate) fn flush<'a>(&mut self) -> Option<Generator<'a, (), String>> {
+        let (feature_type, control) = self.pending.take()?;
+        let commands: Vec<String> = self
+            .firmware
+            .commands(&feature_type, &control)
+            .into_iter()
+            .filter_map(
+                |(register, command)| match self.active.insert(register, command.clone()) {
+                    Some(active) if active == command => None,
+                    _ => Some(command),
+                },
+            )
+            .collect();
+        if commands.is_empty() {
+            tracing::trace!(
+                "Skipping redundant acceleration settings for: {}",
+                feature_type
+            );
+            self.skipped += 1;
+            return None;
+        }
+
+        self.stats[&feature_type] += 1;
+        Some(set_velocity_limit(feature_type, commands))
+    }
+}
 
 pub(crate) fn set_velocity_limit<'a>(
-    feature_type: &'a FeatureType,
-    control: &'a AccelerationControl,
+    feature_type: FeatureType,
+    commands: Vec<String>,
 ) -> Generator<'a, (), String> {
     tracing::debug!("Injecting acceleration settings for: {}", feature_type);
     Gn::new_scoped(move |mut s| {
-        s.yield_with(format!("{} ; {}\n", control, feature_type));
+        for command in commands {
+            s.yield_with(format!("{} ; {}\n", command, feature_type));
+        }
+        done!()
+    })
+}
+
+pub(crate) fn dump_slicer(slicer: &str, version: Option<Version>) -> Generator<'_, (), String> {
+    tracing::debug!("Dumping slicer information");
+    Gn::new_scoped(move |mut s| {
+        s.yield_with("\n".to_string());
+        match version {
+            Some(version) => s.yield_with(format!("; Identified slicer: {slicer} {version}\n")),
+            None => s.yield_with(format!("; Identified slicer: {slicer}\n")),
+        }
+
         done!()
     })
 }
 
-pub(crate) fn dump_settings(settings: &AccelerationSettings) -> Generator<(), String> {
+pub(crate) fn dump_settings(config: &Config) -> Generator<'_, (), String> {
     tracing::debug!("Dumping configuration information");
     Gn::new_scoped(move |mut s| {
         s.yield_with("\n".to_string());
         s.yield_with("; Parsed acceleration values:\n".to_string());
         s.yield_with("\n".to_string());
-        for (feature_type, control) in settings {
+        for (feature_type, control) in &config.features {
             s.yield_with(format!("; {type:<35}{control:?}\n", type=feature_type, control=control))
         }
         s.yield_with("\n".to_string());
+        for (feature_type, ranges) in &config.speeds {
+            for range in ranges {
+                let speeds = format!("{}-{} mm/s", range.min_speed, range.max_speed);
+                s.yield_with(format!(
+                    "; {type:<35}{speeds:<20}{control:?}\n",
+                    type=feature_type, speeds=speeds, control=range.control
+                ))
+            }
+        }
+        if !config.speeds.is_empty() {
+            s.yield_with("\n".to_string());
+        }
 
         done!()
     })
 }
 
-pub(crate) fn dump_stats(stats: &Counter<FeatureType, u64>) -> Generator<(), String> {
+pub(crate) fn dump_stats(limits: &VelocityLimits) -> Generator<'_, (), String> {
     tracing::debug!("Dumping stats");
     Gn::new_scoped(move |mut s| {
         s.yield_with("\n".to_string());
         s.yield_with("; Number of acceleration control insertions:\n".to_string());
         s.yield_with("\n".to_string());
-        for (feature_type, count) in stats {
+        for (feature_type, count) in &limits.stats {
             s.yield_with(format!("; {type:<35}{count}\n", type=feature_type, count=count));
         }
         s.yield_with("\n".to_string());
+        // A request still pending at the end of the file is never followed by a move
+        let skipped = limits.skipped + u64::from(limits.pending.is_some());
+        s.yield_with(format!(
+            "; {:<35}{}\n",
+            "Skipped redundant insertions", skipped
+        ));
+        s.yield_with("\n".to_string());
 
         done!()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            Command::parse("G1 X-10.5 Y.25 E0.02 ; perimeter"),
+            Some(Command {
+                code: "G1".to_string(),
+                params: vec![('X', -10.5), ('Y', 0.25), ('E', 0.02)],
+            })
+        );
+        assert_eq!(
+            Command::parse("N42 G01 F3000 Y10 X20 Z.4*67"),
+            Some(Command {
+                code: "G1".to_string(),
+                params: vec![('F', 3000.0), ('Y', 10.0), ('X', 20.0), ('Z', 0.4)],
+            })
+        );
+        assert_eq!(
+            Command::parse("g0x1y2"),
+            Some(Command {
+                code: "G0".to_string(),
+                params: vec![('X', 1.0), ('Y', 2.0)],
+            })
+        );
+        assert_eq!(
+            Command::parse("G28 X (home X only)"),
+            Some(Command {
+                code: "G28".to_string(),
+                params: vec![('X', 0.0)],
+            })
+        );
+        assert_eq!(Command::parse(";TYPE:Skirt"), None);
+        assert_eq!(Command::parse(""), None);
+        assert_eq!(Command::parse("SET_VELOCITY_LIMIT ACCEL=1000"), None);
+        assert_eq!(Command::parse("PRINT_START EXTRUDER=215 BED=60"), None);
+    }
+
+    #[test]
+    fn test_machine_state() {
+        let mut state = MachineState::default();
+        let mut classify = |line: &str| state.update(&Command::parse(line).unwrap());
+
+        // Absolute extrusion
+        assert_eq!(classify("G1 X10 Y10 E1"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G1 X20 Y10 E1"), Some(MoveKind::Travel));
+        assert_eq!(classify("G1 E0.2"), Some(MoveKind::Retraction));
+        assert_eq!(classify("G92 E0"), None);
+        assert_eq!(classify("G1 X30 E0.5"), Some(MoveKind::Extrusion));
+
+        // Relative extrusion
+        assert_eq!(classify("M83"), None);
+        assert_eq!(classify("G1 X40 Y10 E0"), Some(MoveKind::Travel));
+        assert_eq!(classify("G1 X50 Y10 E-.4"), Some(MoveKind::Wipe));
+        assert_eq!(classify("G1 E-.4 F2100"), Some(MoveKind::Retraction));
+        assert_eq!(classify("G1 Z.6 F720"), Some(MoveKind::ZHop));
+        assert_eq!(classify("G1 F3000"), None);
+        assert_eq!(classify("G1 X50 Y10 E.8"), Some(MoveKind::Retraction));
+
+        // Relative positioning
+        assert_eq!(classify("G91"), None);
+        assert_eq!(classify("G1 Z-.4"), Some(MoveKind::ZHop));
+        assert_eq!(classify("G1 X0 Y5 E.1"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G90"), None);
+        assert_eq!(classify("G1 X50 Y15 Z.2"), None);
+        assert_eq!(state.position, [50.0, 15.0, 0.2]);
+    }
+
+    #[test]
+    fn test_extrusion_mode() {
+        let mut state = MachineState::default();
+        let mut classify = |line: &str| state.update(&Command::parse(line).unwrap());
+
+        // G90 keeps relative extrusion set by M83
+        assert_eq!(classify("M83"), None);
+        assert_eq!(classify("G90"), None);
+        assert_eq!(classify("G1 X10 Y10 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G1 X20 Y10 E.5"), Some(MoveKind::Extrusion));
+
+        // Lifting the nozzle with relative coordinates
+        assert_eq!(classify("G91"), None);
+        assert_eq!(classify("G1 Z.4"), Some(MoveKind::ZHop));
+        assert_eq!(classify("G90"), None);
+        assert_eq!(classify("G1 X30 Y10 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G1 X40 Y10 E.5"), Some(MoveKind::Extrusion));
+
+        // Relative coordinates make extrusion relative until G90 with M82
+        assert_eq!(classify("M82"), None);
+        assert_eq!(classify("G92 E0"), None);
+        assert_eq!(classify("G91"), None);
+        assert_eq!(classify("G1 X10 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G1 X10 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G90"), None);
+        assert_eq!(classify("G1 X70 Y10 E1"), Some(MoveKind::Travel));
+        assert_eq!(classify("G1 X80 Y10 E1.5"), Some(MoveKind::Extrusion));
+    }
+
+    #[test]
+    fn test_machine_state_arcs() {
+        let mut state = MachineState::default();
+        let mut classify = |line: &str| state.update(&Command::parse(line).unwrap());
+
+        assert_eq!(classify("M83"), None);
+        assert_eq!(classify("G1 X10 Y10 F3000"), Some(MoveKind::Travel));
+        assert_eq!(classify("G2 X20 Y10 I5 J0 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G3 X10 Y10 I-5 J0"), Some(MoveKind::Travel));
+        assert_eq!(classify("G2 I5 J0 E1.2"), Some(MoveKind::Extrusion));
+        assert_eq!(classify("G3 X20 Y10 R5 E-.2"), Some(MoveKind::Wipe));
+        assert_eq!(classify("G91"), None);
+        assert_eq!(classify("G2 X10 Y10 I5 J5 E.5"), Some(MoveKind::Extrusion));
+        assert_eq!(state.position, [30.0, 20.0, 0.0]);
+    }
+
+    #[test]
+    fn test_arc_length() {
+        let mut state = MachineState::default();
+        let mut length = |line: &str| {
+            let command = Command::parse(line).unwrap();
+            let from = state.position;
+            state.update(&command);
+            state.xy_path_length(from, &command)
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+
+        assert!(close(length("G1 X100 Y100"), 100.0 * 2.0_f64.sqrt()));
+        assert!(close(length("G2 X100 Y100 I20 J0 E5"), 40.0 * PI));
+        assert!(close(length("G3 X140 Y100 I20 J0"), 20.0 * PI));
+        assert!(close(length("G2 X100 Y100 I-20 J0"), 20.0 * PI));
+        assert!(close(length("G2 X110 Y110 I10 J0"), 5.0 * PI));
+        assert!(close(length("G2 X100 Y100 I0 J-10"), 15.0 * PI));
+        assert!(close(length("G2 X120 Y100 R10"), 10.0 * PI));
+        assert!(close(length("G2 X110 Y110 R-10"), 15.0 * PI));
+    }
+
+    #[test]
+    fn test_firmware_retraction() {
+        let mut state = MachineState::default();
+        let mut classify = |line: &str| state.update(&Command::parse(line).unwrap());
+
+        assert_eq!(classify("G10"), Some(MoveKind::Retraction));
+        assert_eq!(classify("G11"), Some(MoveKind::Retraction));
+        assert_eq!(classify("G10 P0 S215 R180"), None);
+        assert_eq!(classify("G10 L2 P1 X10"), None);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::types::AccelerationSettings;
+use crate::slicers::SlicerOverride;
+use crate::types::Config;
 use anyhow::{Context, Result};
 use clap::{ArgAction, ColorChoice, Parser, ValueHint};
 use std::path::PathBuf;
@@ -12,8 +13,12 @@ mod types;
 /// Preprocess G-Code files to inject fine-grained acceleration control commands per feature.
 ///
 /// Current supported slicers:{n}
-///   * OrcaSlicer{n}
+///   * Bambu Studio{n}
+///   * Cura{n}
+///   * OrcaSlicer (and its forks Creality Print, ElegooSlicer, QIDI Studio, Snapmaker Orca){n}
 ///   * PrusaSlicer{n}
+///   * Simplify3D{n}
+///   * Slic3r{n}
 ///   * SuperSlicer{n}
 #[derive(clap::Parser, Debug)]
 #[clap(author, about, version, name = "Preprocess Cancellation", color=ColorChoice::Auto)]
@@ -24,6 +29,10 @@ pub(crate) struct Cli {
     /// Acceleration control settings
     #[clap(short, long, value_hint=ValueHint::FilePath)]
     pub config: Option<PathBuf>,
+    /// Skip slicer detection and process files as generated by this slicer, e.g. `prusaslicer`,
+    /// an OrcaSlicer fork like `crealityprint` or `custom:<name>` for a dialect from the config
+    #[clap(short, long)]
+    pub slicer: Option<SlicerOverride>,
     /// G-code input files
     #[clap(value_hint=ValueHint::FilePath, num_args=1..)]
     pub gcode: Vec<PathBuf>,
@@ -47,20 +56,18 @@ fn main() -> Result<()> {
     let args = Cli::parse();
     setup_logging(args.verbose)?;
 
-    let mut settings: Option<AccelerationSettings> = None;
-    if let Some(config) = args.config {
-        settings = Some(
-            toml::from_str(
-                &std::fs::read_to_string(config).context("Failed to read configuration file")?,
-            )
-            .context("Failed to parse configuration")?,
-        );
+    let mut config = Config::default();
+    if let Some(path) = args.config {
+        config = toml::from_str(
+            &std::fs::read_to_string(path).context("Failed to read configuration file")?,
+        )
+        .context("Failed to parse configuration")?;
     }
 
     for filename in args.gcode {
         tracing::debug!("Processing GCode file: {}", filename.to_string_lossy());
 
-        let result = preprocess::file(&filename, &settings);
+        let result = preprocess::file(&filename, &config, args.slicer.as_ref());
 
         match result {
             Ok(_) => {
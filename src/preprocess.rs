@@ -1,5 +1,7 @@
-use crate::slicers::{identify_slicer_marker, AccelerationPreProcessor, PreProcessorImpl};
-use crate::types::{AccelerationControl, AccelerationSettings, FeatureType};
+use crate::slicers::{
+    fingerprint, identify_slicer_marker, AccelerationPreProcessor, PreProcessorImpl, SlicerOverride,
+};
+use crate::types::{AccelerationControl, AccelerationSettings, Config, FeatureType};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -17,10 +19,20 @@ pub(crate) enum PreprocessError {
     IoError(#[from] std::io::Error),
     #[error("Invalid numeric value")]
     InvalidNumber(#[from] std::num::ParseIntError),
+    #[error("Invalid minimum cruise ratio")]
+    InvalidRatio(#[from] std::num::ParseFloatError),
+    #[error("Acceleration must be greater than 0")]
+    ZeroAcceleration,
+    #[error("Minimum cruise ratio {0} must be at least 0 and below 1")]
+    RatioOutOfRange(f64),
     #[error("Invalid feature type")]
     InvalidFeatureType(#[from] strum::ParseError),
     #[error("Slicer could not be identified")]
     UnknownSlicer,
+    #[error("Dialect {0} is not defined in the configuration")]
+    UnknownDialect(String),
+    #[error("Velocity caps require max_velocity in the [klipper] section to restore it")]
+    MissingMaxVelocity,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -30,8 +42,9 @@ static ACCELERATION_SETTINGS_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(concat!(
         r#"^;\s*ACCEL\s*:\s*"#,
         r#"(?<accel>\d+)\s*[/\\]\s*"#,
-        r#"(?<accel_to_decel>\d+)\s*[/\\]\s*"#,
-        r#"(?<square_corner_velocity>\d+)\s+"#,
+        r#"((?<minimum_cruise_ratio>\d*\.\d+)|(?<accel_to_decel>\d+))\s*[/\\]\s*"#,
+        r#"(?<square_corner_velocity>\d+)"#,
+        r#"(\s*[/\\]\s*(?<velocity>\d+))?\s+"#,
         r#"for\s+(?<type>.+)"#,
     ))
     .unwrap()
@@ -40,12 +53,27 @@ static ACCELERATION_SETTINGS_REGEX: Lazy<Regex> = Lazy::new(|| {
 fn process(
     input: impl Read + Seek + Send,
     output: &mut impl Write,
-    settings: &Option<AccelerationSettings>,
+    config: &Config,
+    slicer: Option<&SlicerOverride>,
 ) -> Result<(), PreprocessError> {
     let mut input = BufReader::new(input);
-    let mut processor: Option<PreProcessorImpl> = None;
-    let mut settings: AccelerationSettings =
-        settings.as_ref().map_or_else(HashMap::new, |s| s.clone());
+    let mut processor: Option<PreProcessorImpl> = match slicer {
+        Some(slicer) => {
+            tracing::info!("Using slicer from command line: {}", slicer);
+            Some(
+                slicer
+                    .processor(&config.dialects)
+                    .ok_or_else(|| match slicer {
+                        SlicerOverride::Custom(name) => {
+                            PreprocessError::UnknownDialect(name.clone())
+                        }
+                        _ => PreprocessError::UnknownSlicer,
+                    })?,
+            )
+        }
+        None => None,
+    };
+    let mut config = config.clone();
     let mut overrides: AccelerationSettings = HashMap::new();
 
     let mut stop_settings_scan = STOP_SETTINGS_SCAN_AFTER_LINES;
@@ -53,7 +81,7 @@ fn process(
         let line = line.map(|l| l.trim().to_owned())?;
 
         if processor.is_none() {
-            processor = identify_slicer_marker(&line);
+            processor = identify_slicer_marker(&line, &config.dialects);
         }
 
         if let Some(captures) = ACCELERATION_SETTINGS_REGEX.captures(&line) {
@@ -64,16 +92,32 @@ fn process(
                 .expect("Required value for 'accel' not found")
                 .as_str()
                 .parse()?;
-            let accel_to_decel: usize = captures
+            if accel == 0 {
+                return Err(PreprocessError::ZeroAcceleration);
+            }
+            // Decimal values are the minimum cruise ratio, whole numbers accel to decel
+            let accel_to_decel = captures
                 .name("accel_to_decel")
-                .expect("Required value for 'accel_to_decel' not found")
-                .as_str()
-                .parse()?;
+                .map(|m| m.as_str().parse())
+                .transpose()?;
+            let minimum_cruise_ratio: Option<f64> = captures
+                .name("minimum_cruise_ratio")
+                .map(|m| m.as_str().parse())
+                .transpose()?;
+            if let Some(ratio) = minimum_cruise_ratio
+                .filter(|ratio| !AccelerationControl::MINIMUM_CRUISE_RATIO_RANGE.contains(ratio))
+            {
+                return Err(PreprocessError::RatioOutOfRange(ratio));
+            }
             let scv = captures
                 .name("square_corner_velocity")
                 .expect("Required value for 'square_corner_velocity' not found")
                 .as_str()
                 .parse()?;
+            let velocity = captures
+                .name("velocity")
+                .map(|m| m.as_str().parse())
+                .transpose()?;
             let feature_type = FeatureType::from_str(
                 captures
                     .name("type")
@@ -86,7 +130,9 @@ fn process(
                 .or_insert(AccelerationControl {
                     accel,
                     accel_to_decel,
+                    minimum_cruise_ratio,
                     scv,
+                    velocity,
                 });
         } else if !overrides.is_empty() {
             stop_settings_scan -= 1;
@@ -97,7 +143,19 @@ fn process(
     }
 
     // Merge settings from config + settings from gcode
-    settings.extend(overrides);
+    config.features.extend(overrides);
+    if config.has_velocity_caps() && config.klipper.max_velocity.is_none() {
+        return Err(PreprocessError::MissingMaxVelocity);
+    }
+
+    if processor.is_none() {
+        tracing::debug!("No slicer header found, trying to identify slicer by feature markers");
+        input.rewind()?;
+        processor = fingerprint::identify(input.by_ref()).map(|slicer| {
+            tracing::info!("Identified slicer from feature markers: {}", slicer);
+            slicer.processor(None)
+        });
+    }
 
     match &processor {
         None => {
@@ -107,7 +165,7 @@ fn process(
         Some(processor) => {
             input.rewind()?;
 
-            for line in processor.process(input.into_inner(), &settings) {
+            for line in processor.process(input.into_inner(), &config) {
                 write!(output, "{}", line)?;
             }
 
@@ -118,7 +176,8 @@ fn process(
 
 pub(crate) fn file(
     src: &PathBuf,
-    settings: &Option<AccelerationSettings>,
+    config: &Config,
+    slicer: Option<&SlicerOverride>,
 ) -> Result<(), PreprocessError> {
     let dest_path = src.clone();
     let tempfile = NamedTempFile::new()?;
@@ -126,7 +185,7 @@ pub(crate) fn file(
     let reader = BufReader::new(File::open(src)?);
     let mut writer = BufWriter::new(&tempfile);
 
-    match process(reader, &mut writer, settings) {
+    match process(reader, &mut writer, config, slicer) {
         Ok(_) => {
             writer.flush()?;
 
@@ -143,3 +202,377 @@ pub(crate) fn file(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::orcaslicer::OrcaFork;
+    use crate::slicers::SlicerKind;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_acceleration_settings() {
+        let input = Cursor::new(
+            concat!(
+                "; ACCEL: 10000/10000/20  for TYPE:Travel\n",
+                "; ACCEL: 2000/1000/5/150 for TYPE:External perimeter\n",
+                "; ACCEL: 4000/2000/10    for TYPE:Internal perimeter\n",
+                "M83\n",
+                ";TYPE:External perimeter\n",
+                "G1 X10 Y10 E.5\n",
+                ";TYPE:Perimeter\n",
+                "G1 X20 Y10 E.5\n",
+            )
+            .as_bytes(),
+        );
+        let mut output = Vec::new();
+
+        let config: Config = toml::from_str("[klipper]\nmax_velocity = 300").unwrap();
+
+        process(
+            input,
+            &mut output,
+            &config,
+            Some(&SlicerKind::PrusaSlicer.into()),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let control_stmnts: Vec<&str> = output
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 VELOCITY=150 ; TYPE:External perimeter",
+                "SET_VELOCITY_LIMIT ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=10 VELOCITY=300 ; TYPE:Internal perimeter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_velocity_cap_lifted() {
+        let input = concat!(
+            "; ACCEL: 10000/10000/8     for TYPE:Travel\n",
+            "; ACCEL: 2000/1000/5/150   for TYPE:External perimeter\n",
+            "; ACCEL: 2000/1000/5       for TYPE:Internal infill\n",
+            "M83\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5\n",
+            ";TYPE:Internal infill\n",
+            "G1 X20 Y10 E.5\n",
+            "G1 X50 Y50\n",
+        );
+        let config: Config = toml::from_str("[klipper]\nmax_velocity = 300").unwrap();
+        let mut output = Vec::new();
+
+        process(
+            Cursor::new(input.as_bytes()),
+            &mut output,
+            &config,
+            Some(&SlicerKind::PrusaSlicer.into()),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let control_stmnts: Vec<&str> = output
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 VELOCITY=150 ; TYPE:External perimeter",
+                "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 VELOCITY=300 ; TYPE:Internal infill",
+                "SET_VELOCITY_LIMIT ACCEL=10000 ACCEL_TO_DECEL=10000 SQUARE_CORNER_VELOCITY=8 VELOCITY=300 ; TYPE:Travel",
+            ]
+        );
+
+        let result = process(
+            Cursor::new(input.as_bytes()),
+            &mut Vec::new(),
+            &Config::default(),
+            Some(&SlicerKind::PrusaSlicer.into()),
+        );
+        assert!(matches!(result, Err(PreprocessError::MissingMaxVelocity)));
+    }
+
+    #[test]
+    fn test_minimum_cruise_ratio() {
+        let input = concat!(
+            "; ACCEL: 2000/1000/5    for TYPE:External perimeter\n",
+            "; ACCEL: 4000/0.25/10   for TYPE:Internal perimeter\n",
+            "M83\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5\n",
+            ";TYPE:Perimeter\n",
+            "G1 X20 Y10 E.5\n",
+        );
+
+        for (klipper, expected) in [
+            (
+                "",
+                vec![
+                    "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                    "SET_VELOCITY_LIMIT ACCEL=4000 ACCEL_TO_DECEL=3000 SQUARE_CORNER_VELOCITY=10 ; TYPE:Internal perimeter",
+                ],
+            ),
+            (
+                "[klipper]\nversion = \"v0.13.0-12\"",
+                vec![
+                    "SET_VELOCITY_LIMIT ACCEL=2000 MINIMUM_CRUISE_RATIO=0.5 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                    "SET_VELOCITY_LIMIT ACCEL=4000 MINIMUM_CRUISE_RATIO=0.25 SQUARE_CORNER_VELOCITY=10 ; TYPE:Internal perimeter",
+                ],
+            ),
+        ] {
+            let config: Config = toml::from_str(klipper).unwrap();
+            let mut output = Vec::new();
+
+            process(
+                Cursor::new(input.as_bytes()),
+                &mut output,
+                &config,
+                Some(&SlicerKind::PrusaSlicer.into()),
+            )
+            .unwrap();
+            let output = String::from_utf8(output).unwrap();
+            let control_stmnts: Vec<&str> = output
+                .split('\n')
+                .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+                .collect();
+            assert_eq!(control_stmnts, expected, "{klipper}");
+        }
+    }
+
+    #[test]
+    fn test_minimum_cruise_ratio_range() {
+        let result = process(
+            Cursor::new(b"; ACCEL: 2000/1000.0/5 for TYPE:External perimeter\n".as_slice()),
+            &mut Vec::new(),
+            &Config::default(),
+            Some(&SlicerKind::PrusaSlicer.into()),
+        );
+        assert!(matches!(result, Err(PreprocessError::RatioOutOfRange(ratio)) if ratio == 1000.0));
+
+        for ratio in ["1.0", "-0.5"] {
+            let config = format!(
+                "[ExternalPerimeter]\naccel = 2000\nminimum_cruise_ratio = {ratio}\nscv = 5"
+            );
+            assert!(toml::from_str::<Config>(&config).is_err(), "{ratio}");
+        }
+    }
+
+    #[test]
+    fn test_default_minimum_cruise_ratio() {
+        let input = concat!(
+            "M83\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5\n",
+            ";TYPE:Perimeter\n",
+            "G1 X20 Y10 E.5\n",
+        );
+        let features = concat!(
+            "[ExternalPerimeter]\naccel = 2000\nminimum_cruise_ratio = 0.25\nscv = 5\n",
+            "[InternalPerimeter]\naccel = 4000\nscv = 10\n",
+        );
+
+        for (klipper, expected) in [
+            (
+                "",
+                vec![
+                    "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1500 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                    "SET_VELOCITY_LIMIT ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=10 ; TYPE:Internal perimeter",
+                ],
+            ),
+            (
+                "[klipper]\nversion = \"0.13.0\"",
+                vec![
+                    "SET_VELOCITY_LIMIT ACCEL=2000 MINIMUM_CRUISE_RATIO=0.25 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                    "SET_VELOCITY_LIMIT ACCEL=4000 MINIMUM_CRUISE_RATIO=0.5 SQUARE_CORNER_VELOCITY=10 ; TYPE:Internal perimeter",
+                ],
+            ),
+        ] {
+            let config: Config = toml::from_str(&format!("{features}{klipper}")).unwrap();
+            let mut output = Vec::new();
+
+            process(
+                Cursor::new(input.as_bytes()),
+                &mut output,
+                &config,
+                Some(&SlicerKind::PrusaSlicer.into()),
+            )
+            .unwrap();
+            let output = String::from_utf8(output).unwrap();
+            let control_stmnts: Vec<&str> = output
+                .split('\n')
+                .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+                .collect();
+            assert_eq!(control_stmnts, expected, "{klipper}");
+        }
+    }
+
+    #[test]
+    fn test_zero_acceleration() {
+        let result = process(
+            Cursor::new(b"; ACCEL: 0/0/5 for TYPE:External perimeter\n".as_slice()),
+            &mut Vec::new(),
+            &Config::default(),
+            Some(&SlicerKind::PrusaSlicer.into()),
+        );
+        assert!(matches!(result, Err(PreprocessError::ZeroAcceleration)));
+
+        let config = "[ExternalPerimeter]\naccel = 0\naccel_to_decel = 0\nscv = 5";
+        assert!(toml::from_str::<Config>(config).is_err());
+    }
+
+    #[test]
+    fn test_marlin() {
+        let input = concat!(
+            "; ACCEL: 10000/10000/20 for TYPE:Travel\n",
+            "; ACCEL: 2000/1000/5    for TYPE:External perimeter\n",
+            "; ACCEL: 4000/2000/10   for TYPE:Internal perimeter\n",
+            "M83\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5\n",
+            ";TYPE:Perimeter\n",
+            "G1 X20 Y10 E.5\n",
+            "G1 X50 Y50\n",
+        );
+
+        for (marlin, expected) in [
+            (
+                "[marlin]\nretract_accel = 1500",
+                vec![
+                    "M204 P2000 R1500 ; TYPE:External perimeter",
+                    "M205 J0.0052 ; TYPE:External perimeter",
+                    "M204 P4000 R1500 ; TYPE:Internal perimeter",
+                    "M205 J0.0104 ; TYPE:Internal perimeter",
+                    "M204 T10000 R1500 ; TYPE:Travel",
+                    "M205 J0.0166 ; TYPE:Travel",
+                ],
+            ),
+            (
+                "[marlin]\njerk = \"classic\"",
+                vec![
+                    "M204 P2000 ; TYPE:External perimeter",
+                    "M205 X5 Y5 ; TYPE:External perimeter",
+                    "M204 P4000 ; TYPE:Internal perimeter",
+                    "M205 X10 Y10 ; TYPE:Internal perimeter",
+                    "M204 T10000 ; TYPE:Travel",
+                    "M205 X20 Y20 ; TYPE:Travel",
+                ],
+            ),
+        ] {
+            let config: Config = toml::from_str(marlin).unwrap();
+            let mut output = Vec::new();
+
+            process(
+                Cursor::new(input.as_bytes()),
+                &mut output,
+                &config,
+                Some(&SlicerKind::PrusaSlicer.into()),
+            )
+            .unwrap();
+            let output = String::from_utf8(output).unwrap();
+            let control_stmnts: Vec<&str> = output
+                .split('\n')
+                .filter(|line| line.starts_with("M204") || line.starts_with("M205"))
+                .collect();
+            assert_eq!(control_stmnts, expected, "{marlin}");
+        }
+    }
+
+    #[test]
+    fn test_marlin_registers() {
+        let input = concat!(
+            "; ACCEL: 2000/1000/5 for TYPE:Travel\n",
+            "; ACCEL: 2000/1000/5 for TYPE:External perimeter\n",
+            "M83\n",
+            "M204 P1000 R1000 T3000\n",
+            "M205 X8.00 Y8.00\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5\n",
+            "G1 X50 Y50\n",
+            "M204 T3000\n",
+            "G1 X60 Y50 E.5\n",
+        );
+        let config: Config = toml::from_str("[marlin]").unwrap();
+        let mut output = Vec::new();
+
+        process(
+            Cursor::new(input.as_bytes()),
+            &mut output,
+            &config,
+            Some(&SlicerKind::PrusaSlicer.into()),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let control_stmnts: Vec<&str> = output
+            .split('\n')
+            .filter(|line| line.starts_with("M204") || line.starts_with("M205"))
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "M204 P2000 ; TYPE:External perimeter",
+                "M205 J0.0052 ; TYPE:External perimeter",
+                "M204 T2000 ; TYPE:Travel",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slicer_override() {
+        assert_eq!(
+            "prusaslicer".parse(),
+            Ok(SlicerOverride::Builtin(SlicerKind::PrusaSlicer))
+        );
+        assert_eq!(
+            "crealityprint".parse(),
+            Ok(SlicerOverride::Fork(OrcaFork::CrealityPrint))
+        );
+        assert_eq!(
+            "custom:In-house".parse(),
+            Ok(SlicerOverride::Custom("In-house".to_string()))
+        );
+        assert!("ideamaker".parse::<SlicerOverride>().is_err());
+
+        let input = concat!("M83\n", ";FEATURE: perimeter\n", "G1 X10 Y10 E.5\n",);
+        let config: Config = toml::from_str(
+            r#"
+            [[dialects]]
+            name = "In-house"
+            header = "^; In-house slicer"
+            layer_change = ";LAYER"
+            markers = { ";FEATURE: perimeter" = "ExternalPerimeter" }
+
+            [ExternalPerimeter]
+            accel = 2000
+            accel_to_decel = 1000
+            scv = 5
+            "#,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        process(
+            Cursor::new(input.as_bytes()),
+            &mut output,
+            &config,
+            Some(&"custom:In-house".parse().unwrap()),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(
+            "SET_VELOCITY_LIMIT ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter\n"
+        ));
+        assert!(output.contains("; Identified slicer: In-house\n"));
+
+        let result = process(
+            Cursor::new(input.as_bytes()),
+            &mut Vec::new(),
+            &config,
+            Some(&"custom:Unknown".parse().unwrap()),
+        );
+        assert!(matches!(result, Err(PreprocessError::UnknownDialect(_))));
+    }
+}
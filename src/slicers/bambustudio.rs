@@ -0,0 +1,173 @@
+use crate::slicers::slic3r::{Dialect, LayerChange, Markers};
+use crate::slicers::{slic3r, AccelerationPreProcessor};
+use crate::types::{Config, FeatureType, TravelStyle, Version};
+
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+pub(crate) struct BambuStudioProcessor {
+    version: Option<Version>,
+}
+
+impl BambuStudioProcessor {
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> = Version::new(1, 6, 0)..Version::new(2, 1, 0);
+
+    /// Start of a new layer
+    pub(crate) const LAYER_CHANGE: LayerChange<'static> = LayerChange::Marker("; CHANGE_LAYER");
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in Bambu Studio
+            FeatureType::FirstLayer => "; FEATURE: First layer",
+            FeatureType::SmallPerimeter => "; FEATURE: Small perimeter",
+            FeatureType::InternalBridgeInfill => "; FEATURE: Internal bridge infill",
+            FeatureType::ThinWall => "; FEATURE: Thin wall",
+            FeatureType::FloatingVerticalShell => "; FEATURE: Floating vertical shell",
+            FeatureType::Milling => "; FEATURE: Milling",
+
+            // Supported feature types
+            FeatureType::Travel => "; FEATURE: Travel",
+            FeatureType::Custom => "; FEATURE: Custom",
+            FeatureType::ExternalPerimeter => "; FEATURE: Outer wall",
+            FeatureType::OverhangPerimeter => "; FEATURE: Overhang wall",
+            FeatureType::InternalPerimeter => "; FEATURE: Inner wall",
+            FeatureType::TopSolidInfill => "; FEATURE: Top surface",
+            FeatureType::InternalInfill => "; FEATURE: Sparse infill",
+            FeatureType::BridgeInfill => "; FEATURE: Bridge",
+            FeatureType::SolidInfill => "; FEATURE: Internal solid infill",
+            FeatureType::GapFill => "; FEATURE: Gap infill",
+            FeatureType::Skirt => "; FEATURE: Skirt",
+            FeatureType::SupportMaterial => "; FEATURE: Support",
+            FeatureType::SupportMaterialInterface => "; FEATURE: Support interface",
+            FeatureType::Brim => "; FEATURE: Brim",
+            FeatureType::Ironing => "; FEATURE: Ironing",
+            FeatureType::PrimeTower => "; FEATURE: Prime tower",
+            FeatureType::SupportTransition => "; FEATURE: Support transition",
+            FeatureType::BottomSurface => "; FEATURE: Bottom surface",
+            FeatureType::Wipe => "; WIPE_START",
+        }
+    }
+}
+
+impl AccelerationPreProcessor for BambuStudioProcessor /**/ {
+    fn process<'a>(
+        &'a self,
+        input: impl Read + Seek + Send + 'a,
+        config: &'a Config,
+    ) -> generator::Generator<'a, (), String> {
+        let dialect = Dialect {
+            version: self.version,
+            slicer: "Bambu Studio",
+            layer_change: Self::LAYER_CHANGE,
+            travel: TravelStyle::G1,
+            markers: Markers::Builtin(Self::as_marker),
+            wipe_end: Some("; WIPE_END"),
+        };
+
+        slic3r::process(input, config, dialect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
+    use std::fs::File;
+
+    #[test]
+    fn test_bambustudio() {
+        let processor = BambuStudioProcessor::new(Some(Version::new(1, 7, 4)));
+        let input = File::open(GCODE_PATH.join("bambustudio.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            34
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:External perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Bottom surface"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Top solid infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Gap fill"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material interface"))
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Processing functionality for UltiMaker Cura (CuraEngine)
+
+use crate::gcode::{dump_settings, dump_slicer, dump_stats, Command, MachineState, VelocityLimits};
+use crate::slicers::slic3r::{layer_changed, speed_changed, TravelRun};
+use crate::slicers::AccelerationPreProcessor;
+use crate::types::{AccelerationType, Config, FeatureType, Version};
+use generator::{done, Gn};
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::ops::Range;
+use strum::IntoEnumIterator;
+
+pub(crate) struct CuraProcessor {
+    version: Option<Version>,
+}
+
+impl CuraProcessor {
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> = Version::new(4, 0, 0)..Version::new(5, 9, 0);
+
+    /// Comment at the start of every layer, followed by the layer number
+    pub(crate) const LAYER_MARKER: &'static str = ";LAYER:";
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in Cura
+            FeatureType::FirstLayer => ";TYPE:FIRST-LAYER",
+            FeatureType::SmallPerimeter => ";TYPE:SMALL-PERIMETER",
+            FeatureType::Travel => ";TYPE:TRAVEL",
+            FeatureType::Custom => ";TYPE:CUSTOM",
+            FeatureType::OverhangPerimeter => ";TYPE:WALL-OVERHANG",
+            FeatureType::TopSolidInfill => ";TYPE:SKIN-TOP",
+            FeatureType::BridgeInfill => ";TYPE:BRIDGE",
+            FeatureType::InternalBridgeInfill => ";TYPE:BRIDGE-INTERNAL",
+            FeatureType::ThinWall => ";TYPE:WALL-THIN",
+            FeatureType::GapFill => ";TYPE:GAP-FILL",
+            FeatureType::Brim => ";TYPE:BRIM",
+            FeatureType::Ironing => ";TYPE:IRONING",
+            FeatureType::SupportTransition => ";TYPE:SUPPORT-TRANSITION",
+            FeatureType::FloatingVerticalShell => ";TYPE:WALL-FLOATING",
+            FeatureType::BottomSurface => ";TYPE:SKIN-BOTTOM",
+            FeatureType::Wipe => ";TYPE:WIPE",
+            FeatureType::Milling => ";TYPE:MILLING",
+
+            // Supported feature types
+            FeatureType::ExternalPerimeter => ";TYPE:WALL-OUTER",
+            FeatureType::InternalPerimeter => ";TYPE:WALL-INNER",
+            FeatureType::SolidInfill => ";TYPE:SKIN",
+            FeatureType::InternalInfill => ";TYPE:FILL",
+            FeatureType::Skirt => ";TYPE:SKIRT",
+            FeatureType::SupportMaterial => ";TYPE:SUPPORT",
+            FeatureType::SupportMaterialInterface => ";TYPE:SUPPORT-INTERFACE",
+            FeatureType::PrimeTower => ";TYPE:PRIME-TOWER",
+        }
+    }
+}
+
+impl AccelerationPreProcessor for CuraProcessor {
+    fn process<'a>(
+        &'a self,
+        input: impl Read + Seek + Send + 'a,
+        config: &'a Config,
+    ) -> generator::Generator<'a, (), String> {
+        let mut input = BufReader::new(input);
+
+        let mut layer_num: u64 = 0;
+        let mut limits = VelocityLimits::new(config.firmware());
+        let mut last_set_acceleration_type: AccelerationType = AccelerationType::None;
+        let mut current_feature_type: Option<FeatureType> = None;
+        let mut machine = MachineState::default();
+        let mut travel_run: Option<TravelRun> = None;
+
+        Gn::new_scoped_opt(0x8000, move |mut s| {
+            'lines: for line in input.by_ref().lines() {
+                let line = line.unwrap_or("".to_string());
+
+                if line.trim().starts_with("M204 S") {
+                    tracing::trace!(line, "Skipping Marlin Set Starting Acceleration command");
+                    continue;
+                }
+
+                if line.trim().starts_with("SET_VELOCITY_LIMIT") {
+                    tracing::trace!(line, "Skipping Klipper SET_VELOCITY_LIMIT command");
+                    continue;
+                }
+
+                if config.marlin.is_some()
+                    && Command::parse(line.trim())
+                        .is_some_and(|c| matches!(c.code.as_str(), "M204" | "M205"))
+                {
+                    tracing::trace!(line, "Skipping Marlin acceleration and jerk command");
+                    continue;
+                }
+
+                // A travel continues with retractions, Z-hops and further travel moves until
+                // the first print move or the next layer or feature comment
+                if let Some(mut pending) = travel_run.take() {
+                    let mut next = machine;
+                    let command = Command::parse(line.trim());
+                    let move_kind = command.as_ref().and_then(|c| next.update(c));
+                    if !line.trim().starts_with(Self::LAYER_MARKER)
+                        && !line.trim().starts_with(";TYPE:")
+                        && !move_kind.is_some_and(|kind| kind.is_printing())
+                    {
+                        let length = match command {
+                            Some(ref c) if c.code == "G0" => next.xy_distance(machine.position),
+                            _ => 0.0,
+                        };
+                        pending.push(&line, length);
+                        machine = next;
+                        travel_run = Some(pending);
+                        continue;
+                    }
+
+                    let (switched, lines) = pending.finish(config, &mut limits);
+                    if switched {
+                        last_set_acceleration_type = AccelerationType::Travel;
+                    }
+                    s.yield_from(lines);
+                }
+
+                // Cura numbers layers starting from 0 and doesn't emit a separate layer change block
+                if line.trim().starts_with(Self::LAYER_MARKER) {
+                    layer_num += 1;
+                    s.yield_with(format!("{}\n", &line));
+                    layer_changed(
+                        config,
+                        layer_num,
+                        current_feature_type
+                            .filter(|_| last_set_acceleration_type == AccelerationType::Print),
+                        machine.speed(),
+                        &mut limits,
+                    );
+
+                    continue;
+                }
+
+                for feature_type in FeatureType::iter().filter(|ft| config.has_settings(ft)) {
+                    if feature_type.has_marker() && line.trim() == Self::as_marker(&feature_type) {
+                        tracing::trace!("Detected feature type {}", feature_type);
+                        current_feature_type = Some(feature_type);
+                        s.yield_(format!("{}\n", line));
+                        if let Some((label, control)) =
+                            config.control(&feature_type, layer_num, machine.speed())
+                        {
+                            limits.set(&label, &control);
+                        }
+                        last_set_acceleration_type = AccelerationType::Print;
+
+                        continue 'lines;
+                    }
+                }
+
+                // Cura uses G0 exclusively for travel moves and G1-G3 for everything else,
+                // retractions and Z-hops around a travel move belong to the travel
+                let command = Command::parse(line.trim());
+                let start = machine.position;
+                let move_kind = command.as_ref().and_then(|c| machine.update(c));
+                if command.as_ref().is_some_and(|c| c.code == "G0") {
+                    if last_set_acceleration_type != AccelerationType::Travel {
+                        travel_run = Some(TravelRun::new(&line, machine.xy_distance(start)));
+                        continue;
+                    }
+                } else if move_kind.is_some_and(|kind| kind.is_printing())
+                    && last_set_acceleration_type == AccelerationType::Travel
+                {
+                    if let Some((label, control)) = current_feature_type.and_then(|feature_type| {
+                        config.control(&feature_type, layer_num, machine.speed())
+                    }) {
+                        limits.set(&label, &control);
+                        last_set_acceleration_type = AccelerationType::Print;
+                    }
+                }
+
+                if last_set_acceleration_type == AccelerationType::Print
+                    && move_kind.is_some_and(|kind| kind.is_printing())
+                {
+                    speed_changed(
+                        config,
+                        layer_num,
+                        current_feature_type,
+                        machine.speed(),
+                        &mut limits,
+                    );
+                }
+
+                if move_kind.is_some() || command.as_ref().is_some_and(|c| c.code == "G0") {
+                    if let Some(limit) = limits.flush() {
+                        s.yield_from(limit);
+                    }
+                }
+                s.yield_with(format!("{}\n", &line));
+            }
+
+            if let Some(pending) = travel_run.take() {
+                s.yield_from(pending.finish(config, &mut limits).1);
+            }
+
+            s.yield_from(dump_slicer("Cura", self.version));
+            s.yield_from(dump_settings(config));
+            s.yield_from(dump_stats(&limits));
+
+            done!();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
+    use crate::types::TravelConfig;
+    use std::fs::File;
+
+    #[test]
+    fn test_cura() {
+        let processor = CuraProcessor::new(Some(Version::new(5, 4, 0)));
+        let input = File::open(GCODE_PATH.join("cura.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        assert!(result.contains(&"; Identified slicer: Cura 5.4.0"));
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            0
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            33
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:External perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Solid infill"))
+                .count(),
+            16
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material interface"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cura_segmented_travel() {
+        let processor = CuraProcessor::new(None);
+        let input = format!(
+            "M83\n;TYPE:FILL\nG1 X10 Y10 E.5\nG1 E-.8\n{}G1 E.8\nG1 X39 Y10 E.5\n",
+            (1..=7)
+                .map(|i| format!("G0 X{} Y10\n", 10 + 4 * i))
+                .collect::<String>()
+        );
+        let config = Config {
+            travel: TravelConfig { min_distance: 5.0 },
+            ..CONFIG.clone()
+        };
+
+        let result: String = processor
+            .process(std::io::Cursor::new(input.as_bytes()), &config)
+            .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:Internal infill",
+                "TYPE:Travel",
+                "TYPE:Internal infill"
+            ]
+        );
+    }
+}
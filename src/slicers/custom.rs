@@ -0,0 +1,92 @@
+use crate::slicers::slic3r::Dialect;
+use crate::slicers::{slic3r, AccelerationPreProcessor};
+use crate::types::{Config, DialectConfig};
+
+use std::io::{Read, Seek};
+
+/// Processor for slicer dialects defined in the configuration file
+pub(crate) struct CustomProcessor {
+    dialect: DialectConfig,
+}
+
+impl CustomProcessor {
+    pub fn new(dialect: DialectConfig) -> Self {
+        Self { dialect }
+    }
+}
+
+impl AccelerationPreProcessor for CustomProcessor /**/ {
+    fn process<'a>(
+        &'a self,
+        input: impl Read + Seek + Send + 'a,
+        config: &'a Config,
+    ) -> generator::Generator<'a, (), String> {
+        slic3r::process(input, config, Dialect::from(&self.dialect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
+    use crate::types::Config;
+    use std::fs::File;
+
+    #[test]
+    fn test_custom_dialect() {
+        let config: Config = toml::from_str(
+            r#"
+            [[dialects]]
+            name = "In-house"
+            header = "^; BambuStudio"
+            layer_change = "; CHANGE_LAYER"
+
+            [dialects.markers]
+            "; FEATURE: Outer wall" = "ExternalPerimeter"
+            "; FEATURE: Bottom surface" = "SolidInfill"
+            "; FEATURE: Internal solid infill" = "SolidInfill"
+            "#,
+        )
+        .unwrap();
+        let processor = CustomProcessor::new(config.dialects[0].clone());
+        let input = File::open(GCODE_PATH.join("bambustudio.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        assert!(result.contains(&"; Identified slicer: In-house"));
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            32
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:External perimeter"))
+                .count(),
+            22
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Solid infill"))
+                .count(),
+            9
+        );
+    }
+}
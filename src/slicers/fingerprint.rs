@@ -0,0 +1,91 @@
+//! Slicer identification by the comments found in the G-Code output, used when the
+//! header identifying the slicer has been removed or rewritten by other tools
+
+use crate::slicers::slic3r::LayerChange;
+use crate::slicers::SlicerKind;
+use crate::types::FeatureType;
+use std::collections::HashSet;
+use std::io::BufRead;
+use strum::IntoEnumIterator;
+
+struct Candidate {
+    slicer: SlicerKind,
+    markers: HashSet<String>,
+    found: HashSet<String>,
+    layer_change_found: bool,
+}
+
+impl Candidate {
+    fn new(slicer: SlicerKind) -> Self {
+        let as_marker = slicer.as_marker();
+        Self {
+            slicer,
+            markers: FeatureType::iter()
+                .filter(FeatureType::has_marker)
+                .map(|feature_type| as_marker(&feature_type).to_string())
+                .collect(),
+            found: HashSet::new(),
+            layer_change_found: false,
+        }
+    }
+
+    /// Number of distinct markers found, slicers that mark layer changes with
+    /// a comment only qualify if it was found.
+    fn score(&self) -> Option<usize> {
+        match self.slicer.layer_change() {
+            LayerChange::ZHeight => Some(self.found.len()),
+            _ if self.layer_change_found => Some(self.found.len() + 1),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the slicer by the feature and layer change markers used in a file
+pub(crate) fn identify(input: impl BufRead) -> Option<SlicerKind> {
+    let mut candidates: Vec<Candidate> = SlicerKind::iter().map(Candidate::new).collect();
+
+    for line in input.lines().map_while(Result::ok) {
+        let line = line.trim();
+        if !line.starts_with(';') {
+            continue;
+        }
+
+        for candidate in candidates.iter_mut() {
+            if candidate.markers.contains(line) && !candidate.found.contains(line) {
+                candidate.found.insert(line.to_string());
+            }
+            candidate.layer_change_found |= candidate.slicer.layer_change().matches(line);
+        }
+    }
+
+    candidates
+        .iter()
+        .filter_map(|candidate| Some((candidate.score()?, candidate.slicer)))
+        .filter(|(score, _)| *score > 1)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, slicer)| slicer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::GCODE_PATH;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_fingerprint() {
+        for (filename, slicer) in [
+            ("bambustudio.gcode", SlicerKind::BambuStudio),
+            ("cura.gcode", SlicerKind::Cura),
+            ("orcaslicer.gcode", SlicerKind::Orca),
+            ("prusaslicer.gcode", SlicerKind::PrusaSlicer),
+            ("simplify3d.gcode", SlicerKind::Simplify3D),
+            ("slic3r.gcode", SlicerKind::Slic3r),
+            ("superslicer.gcode", SlicerKind::SuperSlicer),
+        ] {
+            let input = BufReader::new(File::open(GCODE_PATH.join(filename)).unwrap());
+            assert_eq!(identify(input), Some(slicer), "{}", filename);
+        }
+    }
+}
@@ -1,42 +1,236 @@
 use std::io::{Read, Seek};
+use std::ops::Range;
 
+pub(crate) mod bambustudio;
+pub(crate) mod cura;
+pub(crate) mod custom;
+pub(crate) mod fingerprint;
 pub(crate) mod orcaslicer;
 pub(crate) mod prusaslicer;
+pub(crate) mod simplify3d;
 pub(crate) mod slic3r;
+pub(crate) mod slic3r_original;
 pub(crate) mod superslicer;
 
-use crate::types::AccelerationSettings;
-use orcaslicer::OrcaSlicerProcessor as Orca;
+use crate::slicers::slic3r::{LayerChange, LAYER_CHANGE_MARKER};
+use crate::types::{Config, DialectConfig, FeatureType, Version};
+use bambustudio::BambuStudioProcessor as BambuStudio;
+use cura::CuraProcessor as Cura;
+use custom::CustomProcessor as Custom;
+use orcaslicer::{OrcaFork, OrcaSlicerProcessor as Orca};
 use prusaslicer::PrusaSlicerProcessor as PrusaSlicer;
+use simplify3d::Simplify3DProcessor as Simplify3D;
+use slic3r_original::Slic3rProcessor as Slic3r;
 use superslicer::SuperSlicerProcessor as SuperSlicer;
 
 #[enum_dispatch::enum_dispatch]
 pub(crate) enum PreProcessorImpl {
+    BambuStudio,
+    Cura,
+    Custom,
     Orca,
     PrusaSlicer,
+    Simplify3D,
+    Slic3r,
     SuperSlicer,
 }
 
+/// Slicers with built-in support
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum, strum::Display, strum::EnumIter)]
+#[value(rename_all = "lower")]
+pub(crate) enum SlicerKind {
+    #[strum(serialize = "Bambu Studio")]
+    BambuStudio,
+    Cura,
+    #[strum(serialize = "OrcaSlicer")]
+    Orca,
+    PrusaSlicer,
+    Simplify3D,
+    Slic3r,
+    SuperSlicer,
+}
+
+impl SlicerKind {
+    pub(crate) fn processor(&self, version: Option<Version>) -> PreProcessorImpl {
+        match self {
+            SlicerKind::BambuStudio => BambuStudio::new(version).into(),
+            SlicerKind::Cura => Cura::new(version).into(),
+            SlicerKind::Orca => Orca::new(OrcaFork::OrcaSlicer, version).into(),
+            SlicerKind::PrusaSlicer => PrusaSlicer::new(version).into(),
+            SlicerKind::Simplify3D => Simplify3D::new(version).into(),
+            SlicerKind::Slic3r => Slic3r::new(version).into(),
+            SlicerKind::SuperSlicer => SuperSlicer::new(version).into(),
+        }
+    }
+
+    /// Start of a new layer in the slicer's G-Code output
+    pub(crate) fn layer_change(&self) -> LayerChange<'static> {
+        match self {
+            SlicerKind::BambuStudio => BambuStudio::LAYER_CHANGE,
+            SlicerKind::Cura => LayerChange::Marker(Cura::LAYER_MARKER),
+            SlicerKind::Orca | SlicerKind::PrusaSlicer | SlicerKind::SuperSlicer => {
+                LayerChange::Marker(LAYER_CHANGE_MARKER)
+            }
+            SlicerKind::Simplify3D => Simplify3D::LAYER_CHANGE,
+            SlicerKind::Slic3r => Slic3r::LAYER_CHANGE,
+        }
+    }
+
+    /// Marker table of the current release of the slicer
+    pub(crate) fn as_marker(&self) -> fn(&FeatureType) -> &str {
+        match self {
+            SlicerKind::BambuStudio => BambuStudio::as_marker,
+            SlicerKind::Cura => Cura::as_marker,
+            SlicerKind::Orca => Orca::as_marker,
+            SlicerKind::PrusaSlicer => PrusaSlicer::as_marker,
+            SlicerKind::Simplify3D => Simplify3D::as_marker,
+            SlicerKind::Slic3r => Slic3r::as_marker,
+            SlicerKind::SuperSlicer => SuperSlicer::as_marker,
+        }
+    }
+}
+
+/// Slicer forced on the command line with `--slicer`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SlicerOverride {
+    /// Slicer with built-in support, e.g. `prusaslicer`
+    Builtin(SlicerKind),
+    /// Fork of OrcaSlicer, e.g. `crealityprint`
+    Fork(OrcaFork),
+    /// Dialect defined in the configuration file, e.g. `custom:In-house`
+    Custom(String),
+}
+
+impl SlicerOverride {
+    pub(crate) fn processor(&self, dialects: &[DialectConfig]) -> Option<PreProcessorImpl> {
+        match self {
+            SlicerOverride::Builtin(slicer) => Some(slicer.processor(None)),
+            SlicerOverride::Fork(fork) => Some(Orca::new(*fork, None).into()),
+            SlicerOverride::Custom(name) => dialects
+                .iter()
+                .find(|dialect| &dialect.name == name)
+                .map(|dialect| Custom::new(dialect.clone()).into()),
+        }
+    }
+}
+
+impl From<SlicerKind> for SlicerOverride {
+    fn from(slicer: SlicerKind) -> Self {
+        SlicerOverride::Builtin(slicer)
+    }
+}
+
+impl std::str::FromStr for SlicerOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use clap::ValueEnum;
+
+        if let Some(name) = s.strip_prefix("custom:") {
+            return Ok(SlicerOverride::Custom(name.to_string()));
+        }
+        SlicerKind::from_str(s, true)
+            .map(SlicerOverride::Builtin)
+            .or_else(|_| OrcaFork::from_str(s, true).map(SlicerOverride::Fork))
+            .map_err(|_| {
+                let names: Vec<String> = SlicerKind::value_variants()
+                    .iter()
+                    .filter_map(|slicer| slicer.to_possible_value())
+                    .chain(
+                        OrcaFork::value_variants()
+                            .iter()
+                            .filter_map(|fork| fork.to_possible_value()),
+                    )
+                    .map(|value| value.get_name().to_string())
+                    .collect();
+                format!(
+                    "unknown slicer '{s}', expected one of {} or custom:<name>",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for SlicerOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlicerOverride::Builtin(slicer) => write!(f, "{slicer}"),
+            SlicerOverride::Fork(fork) => write!(f, "{fork}"),
+            SlicerOverride::Custom(name) => write!(f, "{name} (user-defined)"),
+        }
+    }
+}
+
 #[enum_dispatch::enum_dispatch(PreProcessorImpl)]
 pub(crate) trait AccelerationPreProcessor {
     fn process<'a>(
         &'a self,
         input: impl Read + Seek + Send + 'a,
-        settings: &'a AccelerationSettings,
+        config: &'a Config,
     ) -> generator::Generator<'a, (), String>;
 }
 
-pub(crate) fn identify_slicer_marker(line: &str) -> Option<PreProcessorImpl> {
+/// Warns if the marker table of a slicer wasn't tested with its version
+fn check_version(slicer: &str, version: Version, tested_versions: &Range<Version>) {
+    if !tested_versions.contains(&version) {
+        tracing::warn!(
+            "{} {} is untested, features might not be detected correctly (tested versions: {} up to {})",
+            slicer,
+            version,
+            tested_versions.start,
+            tested_versions.end
+        );
+    }
+}
+
+fn identified(slicer: &str, version: Option<Version>, tested_versions: Range<Version>) {
+    match version {
+        Some(version) => {
+            tracing::info!("Identified slicer from header: {} {}", slicer, version);
+            check_version(slicer, version, &tested_versions);
+        }
+        None => tracing::info!(
+            "Identified slicer from header: {} (unknown version)",
+            slicer
+        ),
+    }
+}
+
+pub(crate) fn identify_slicer_marker(
+    line: &str,
+    dialects: &[DialectConfig],
+) -> Option<PreProcessorImpl> {
     let line = line.trim();
+    if let Some(dialect) = dialects.iter().find(|d| d.header.is_match(line)) {
+        tracing::info!(
+            "Identified slicer from header: {} (user-defined)",
+            dialect.name
+        );
+        return Some(Custom::new(dialect.clone()).into());
+    }
+
+    let version = Version::find(line);
     if line.starts_with("; generated by SuperSlicer") {
-        tracing::info!("Identified slicer: SuperSlicer");
-        Some(SuperSlicer::new().into())
+        identified("SuperSlicer", version, SuperSlicer::TESTED_VERSIONS);
+        Some(SuperSlicer::new(version).into())
     } else if line.starts_with("; generated by PrusaSlicer") {
-        tracing::info!("Identified slicer: PrusaSlicer");
-        Some(PrusaSlicer::new().into())
-    } else if line.starts_with("; generated by OrcaSlicer") {
-        tracing::info!("Identified slicer: OrcaSlicer");
-        Some(Orca::new().into())
+        identified("PrusaSlicer", version, PrusaSlicer::TESTED_VERSIONS);
+        Some(PrusaSlicer::new(version).into())
+    } else if let Some(fork) = OrcaFork::identify(line) {
+        identified(fork.into(), version, fork.tested_versions());
+        Some(Orca::new(fork, version).into())
+    } else if line.starts_with("; BambuStudio") || line.starts_with("; generated by BambuStudio") {
+        identified("Bambu Studio", version, BambuStudio::TESTED_VERSIONS);
+        Some(BambuStudio::new(version).into())
+    } else if line.starts_with("; generated by Slic3r") {
+        identified("Slic3r", version, Slic3r::TESTED_VERSIONS);
+        Some(Slic3r::new(version).into())
+    } else if line.starts_with("; G-Code generated by Simplify3D") {
+        identified("Simplify3D", version, Simplify3D::TESTED_VERSIONS);
+        Some(Simplify3D::new(version).into())
+    } else if line.starts_with(";Generated with Cura_SteamEngine") {
+        identified("Cura", version, Cura::TESTED_VERSIONS);
+        Some(Cura::new(version).into())
     } else {
         None
     }
@@ -44,7 +238,10 @@ pub(crate) fn identify_slicer_marker(line: &str) -> Option<PreProcessorImpl> {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use crate::types::{AccelerationControl, AccelerationSettings, FeatureType};
+    use super::{identify_slicer_marker, PreProcessorImpl};
+    use crate::types::{
+        AccelerationControl, AccelerationSettings, Config, FeatureType, FirstLayerConfig, Version,
+    };
     use once_cell::sync::Lazy;
     use std::collections::HashMap;
     use std::path::{Path, PathBuf};
@@ -58,115 +255,262 @@ pub(crate) mod tests {
             FeatureType::FirstLayer,
             AccelerationControl {
                 accel: 2000,
-                accel_to_decel: 1000,
+                accel_to_decel: Some(1000),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::Travel,
             AccelerationControl {
                 accel: 10000,
-                accel_to_decel: 10000,
+                accel_to_decel: Some(10000),
+                minimum_cruise_ratio: None,
                 scv: 8,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::ExternalPerimeter,
             AccelerationControl {
-                accel: 2000,
-                accel_to_decel: 1000,
+                accel: 2200,
+                accel_to_decel: Some(1100),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::OverhangPerimeter,
             AccelerationControl {
-                accel: 2000,
-                accel_to_decel: 1000,
+                accel: 1800,
+                accel_to_decel: Some(900),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::InternalPerimeter,
             AccelerationControl {
                 accel: 2500,
-                accel_to_decel: 1750,
+                accel_to_decel: Some(1750),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::TopSolidInfill,
             AccelerationControl {
-                accel: 2500,
-                accel_to_decel: 1750,
+                accel: 2600,
+                accel_to_decel: Some(1800),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::SolidInfill,
             AccelerationControl {
                 accel: 4000,
-                accel_to_decel: 2000,
+                accel_to_decel: Some(2000),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::InternalInfill,
             AccelerationControl {
                 accel: 6000,
-                accel_to_decel: 3000,
+                accel_to_decel: Some(3000),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::InternalBridgeInfill,
             AccelerationControl {
-                accel: 4000,
-                accel_to_decel: 2000,
+                accel: 3800,
+                accel_to_decel: Some(1900),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::ThinWall,
             AccelerationControl {
-                accel: 2000,
-                accel_to_decel: 1000,
+                accel: 1900,
+                accel_to_decel: Some(950),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::GapFill,
             AccelerationControl {
-                accel: 2000,
-                accel_to_decel: 1000,
+                accel: 2300,
+                accel_to_decel: Some(1150),
+                minimum_cruise_ratio: None,
                 scv: 5,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::Skirt,
             AccelerationControl {
                 accel: 4000,
-                accel_to_decel: 2000,
+                accel_to_decel: Some(2000),
+                minimum_cruise_ratio: None,
                 scv: 8,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::SupportMaterial,
             AccelerationControl {
                 accel: 6000,
-                accel_to_decel: 3000,
+                accel_to_decel: Some(3000),
+                minimum_cruise_ratio: None,
                 scv: 8,
+                velocity: None,
             },
         );
         settings.insert(
             FeatureType::SupportMaterialInterface,
             AccelerationControl {
-                accel: 6000,
-                accel_to_decel: 3000,
+                accel: 5500,
+                accel_to_decel: Some(2750),
+                minimum_cruise_ratio: None,
+                scv: 8,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::Brim,
+            AccelerationControl {
+                accel: 4200,
+                accel_to_decel: Some(2100),
+                minimum_cruise_ratio: None,
+                scv: 8,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::Ironing,
+            AccelerationControl {
+                accel: 1700,
+                accel_to_decel: Some(850),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::PrimeTower,
+            AccelerationControl {
+                accel: 5000,
+                accel_to_decel: Some(2500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::SupportTransition,
+            AccelerationControl {
+                accel: 5800,
+                accel_to_decel: Some(2900),
+                minimum_cruise_ratio: None,
                 scv: 8,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::FloatingVerticalShell,
+            AccelerationControl {
+                accel: 2400,
+                accel_to_decel: Some(1200),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::Milling,
+            AccelerationControl {
+                accel: 1000,
+                accel_to_decel: Some(500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::BottomSurface,
+            AccelerationControl {
+                accel: 3000,
+                accel_to_decel: Some(1500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+        settings.insert(
+            FeatureType::Wipe,
+            AccelerationControl {
+                accel: 5200,
+                accel_to_decel: Some(2600),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
             },
         );
 
         settings
     });
+
+    /// Feature detection is tested without the first layer settings taking precedence, every
+    /// feature has distinct settings so none of them are skipped as redundant
+    pub(crate) static CONFIG: Lazy<Config> = Lazy::new(|| Config {
+        first_layer: FirstLayerConfig {
+            layers: 0,
+            ..Default::default()
+        },
+        features: SETTINGS.clone(),
+        ..Default::default()
+    });
+
+    #[test]
+    fn test_header_version() {
+        assert_eq!(
+            Version::find(
+                "; generated by PrusaSlicer 2.4.0-alpha1+win64 on 2021-09-03 at 16:52:36 UTC"
+            ),
+            Some(Version::new(2, 4, 0))
+        );
+        assert_eq!(
+            Version::find("; BambuStudio 01.07.04.52"),
+            Some(Version::new(1, 7, 4))
+        );
+        assert_eq!(
+            Version::find("; G-Code generated by Simplify3D(R) Version 4.1.2"),
+            Some(Version::new(4, 1, 2))
+        );
+        assert_eq!(Version::find(";FLAVOR:Marlin"), None);
+    }
+
+    #[test]
+    fn test_identify_cura() {
+        assert!(identify_slicer_marker(";FLAVOR:Marlin", &[]).is_none());
+        assert!(matches!(
+            identify_slicer_marker(";Generated with Cura_SteamEngine 5.4.0", &[]),
+            Some(PreProcessorImpl::Cura(_))
+        ));
+    }
 }
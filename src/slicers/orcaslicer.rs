@@ -1,13 +1,134 @@
+use crate::slicers::slic3r::Dialect;
 use crate::slicers::{slic3r, AccelerationPreProcessor};
-use crate::types::{AccelerationSettings, FeatureType};
+use crate::types::{Config, FeatureType, Version};
 
 use std::io::{Read, Seek};
+use std::ops::Range;
 
-pub(crate) struct OrcaSlicerProcessor {}
+/// OrcaSlicer and vendor slicers forked from it that share its G-Code vocabulary
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    strum::Display,
+    strum::EnumIter,
+    strum::IntoStaticStr,
+)]
+#[value(rename_all = "lower")]
+pub(crate) enum OrcaFork {
+    #[strum(serialize = "OrcaSlicer")]
+    OrcaSlicer,
+    #[strum(serialize = "Creality Print")]
+    CrealityPrint,
+    #[strum(serialize = "ElegooSlicer")]
+    ElegooSlicer,
+    #[strum(serialize = "QIDI Studio")]
+    QidiStudio,
+    #[strum(serialize = "Snapmaker Orca")]
+    SnapmakerOrca,
+}
+
+impl OrcaFork {
+    /// Names the fork writes into the `; generated by` header line
+    fn header_names(&self) -> &'static [&'static str] {
+        match self {
+            OrcaFork::OrcaSlicer => &["OrcaSlicer"],
+            OrcaFork::CrealityPrint => &["CrealityPrint", "Creality Print"],
+            OrcaFork::ElegooSlicer => &["ElegooSlicer"],
+            OrcaFork::QidiStudio => &["QIDIStudio", "QIDI Studio"],
+            OrcaFork::SnapmakerOrca => &["Snapmaker_Orca", "Snapmaker Orca"],
+        }
+    }
+
+    pub(crate) fn identify(line: &str) -> Option<Self> {
+        let name = line.strip_prefix("; generated by ")?;
+        <Self as strum::IntoEnumIterator>::iter().find(|fork| {
+            fork.header_names()
+                .iter()
+                .any(|header_name| name.starts_with(header_name))
+        })
+    }
+
+    /// Versions of the fork the marker table has been tested with
+    pub(crate) fn tested_versions(&self) -> Range<Version> {
+        match self {
+            OrcaFork::OrcaSlicer => Version::new(1, 5, 0)..Version::new(2, 4, 0),
+            OrcaFork::CrealityPrint => Version::new(5, 0, 0)..Version::new(6, 1, 0),
+            OrcaFork::ElegooSlicer => Version::new(1, 0, 0)..Version::new(1, 2, 0),
+            OrcaFork::QidiStudio => Version::new(1, 0, 0)..Version::new(2, 1, 0),
+            OrcaFork::SnapmakerOrca => Version::new(2, 0, 0)..Version::new(2, 2, 0),
+        }
+    }
+
+    /// Marker table used for a release of the fork. The forks currently share the markers of
+    /// OrcaSlicer, a fork whose vocabulary differs needs its own table here falling back to
+    /// [`OrcaSlicerProcessor::as_marker`] for the features it doesn't rename.
+    fn as_marker(&self, version: Option<Version>) -> fn(&FeatureType) -> &str {
+        match self {
+            OrcaFork::OrcaSlicer if version.is_some_and(|v| v < Version::new(2, 0, 0)) => {
+                OrcaSlicerProcessor::as_marker_v1
+            }
+            OrcaFork::OrcaSlicer
+            | OrcaFork::CrealityPrint
+            | OrcaFork::ElegooSlicer
+            | OrcaFork::QidiStudio
+            | OrcaFork::SnapmakerOrca => OrcaSlicerProcessor::as_marker,
+        }
+    }
+}
+
+pub(crate) struct OrcaSlicerProcessor {
+    fork: OrcaFork,
+    version: Option<Version>,
+}
 
 impl OrcaSlicerProcessor {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(fork: OrcaFork, version: Option<Version>) -> Self {
+        Self { fork, version }
+    }
+
+    /// OrcaSlicer 1.x doesn't distinguish internal bridges from bridges
+    fn as_marker_v1(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
+            _ => Self::as_marker(feature_type),
+        }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in OrcaSlicer
+            FeatureType::FirstLayer => ";TYPE:First layer",
+            FeatureType::SmallPerimeter => ";TYPE:Small perimeter",
+            FeatureType::Milling => ";TYPE:Milling",
+
+            // Supported feature types
+            FeatureType::InternalBridgeInfill => ";TYPE:Internal Bridge",
+            FeatureType::Travel => ";TYPE:Travel",
+            FeatureType::BottomSurface => ";TYPE:Bottom surface",
+            FeatureType::Custom => ";TYPE:Custom",
+            FeatureType::ExternalPerimeter => ";TYPE:Outer wall",
+            FeatureType::OverhangPerimeter => ";TYPE:Overhang wall",
+            FeatureType::InternalPerimeter => ";TYPE:Inner wall",
+            FeatureType::TopSolidInfill => ";TYPE:Top surface",
+            FeatureType::InternalInfill => ";TYPE:Sparse infill",
+            FeatureType::BridgeInfill => ";TYPE:Bridge",
+            FeatureType::SolidInfill => ";TYPE:Internal solid infill",
+            FeatureType::ThinWall => ";TYPE:Thin wall",
+            FeatureType::GapFill => ";TYPE:Gap infill",
+            FeatureType::Skirt => ";TYPE:Skirt",
+            FeatureType::SupportMaterial => ";TYPE:Support",
+            FeatureType::SupportMaterialInterface => ";TYPE:Support interface",
+            FeatureType::Brim => ";TYPE:Brim",
+            FeatureType::Ironing => ";TYPE:Ironing",
+            FeatureType::PrimeTower => ";TYPE:Prime tower",
+            FeatureType::SupportTransition => ";TYPE:Support transition",
+            FeatureType::FloatingVerticalShell => ";TYPE:Floating vertical shell",
+            FeatureType::Wipe => ";WIPE_START",
+        }
     }
 }
 
@@ -15,49 +136,33 @@ impl AccelerationPreProcessor for OrcaSlicerProcessor /**/ {
     fn process<'a>(
         &'a self,
         input: impl Read + Seek + Send + 'a,
-        settings: &'a AccelerationSettings,
+        config: &'a Config,
     ) -> generator::Generator<'a, (), String> {
-        let as_marker: fn(&FeatureType) -> &str = |feature_type: &FeatureType| {
-            match feature_type {
-                // Not implemented in OrcaSlicer
-                FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
-
-                // Supported feature types
-                FeatureType::Travel => ";TYPE:Travel",
-                FeatureType::FirstLayer => ";TYPE:Bottom surface",
-                FeatureType::Custom => ";TYPE:Custom",
-                FeatureType::ExternalPerimeter => ";TYPE:Outer wall",
-                FeatureType::OverhangPerimeter => ";TYPE:Overhang wall",
-                FeatureType::InternalPerimeter => ";TYPE:Inner wall",
-                FeatureType::TopSolidInfill => ";TYPE:Top surface",
-                FeatureType::InternalInfill => ";TYPE:Sparse infill",
-                FeatureType::BridgeInfill => ";TYPE:Bridge",
-                FeatureType::SolidInfill => ";TYPE:Internal solid infill",
-                FeatureType::ThinWall => ";TYPE:Thin wall",
-                FeatureType::GapFill => ";TYPE:Gap infill",
-                FeatureType::Skirt => ";TYPE:Skirt",
-                FeatureType::SupportMaterial => ";TYPE:Support",
-                FeatureType::SupportMaterialInterface => ";TYPE:Support interface",
-            }
-        };
+        let dialect = Dialect::new(
+            self.fork.into(),
+            self.version,
+            self.fork.as_marker(self.version),
+        );
 
-        slic3r::process(input, settings, as_marker)
+        slic3r::process(input, config, dialect)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::slicers::tests::{GCODE_PATH, SETTINGS};
+    use crate::slicers::identify_slicer_marker;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
     use std::fs::File;
 
     #[test]
     fn test_orcaslicer() {
-        let processor = OrcaSlicerProcessor::new();
+        let processor = OrcaSlicerProcessor::new(OrcaFork::OrcaSlicer, Some(Version::new(1, 5, 0)));
         let input = File::open(GCODE_PATH.join("orcaslicer.gcode")).unwrap();
 
-        let result: String = processor.process(input, &SETTINGS).collect();
+        let result: String = processor.process(input, &CONFIG).collect();
         let result: Vec<&str> = result.split('\n').collect();
+        assert!(result.contains(&"; Identified slicer: OrcaSlicer 1.5.0"));
         let control_stmnts: Vec<&str> = result
             .iter()
             .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
@@ -76,49 +181,63 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Top solid infill"))
                 .count(),
-            7
+            4
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Travel"))
                 .count(),
-            830
+            854
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Solid infill"))
                 .count(),
-            479
+            388
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Internal infill"))
                 .count(),
-            10
+            5
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Internal perimeter"))
                 .count(),
-            233
+            230
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:First Layer"))
                 .count(),
-            7
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Bottom surface"))
+                .count(),
+            5
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:External perimeter"))
                 .count(),
-            326
+            116
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Wipe"))
+                .count(),
+            227
         );
         assert_eq!(
             control_stmnts
@@ -128,4 +247,205 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn test_identify_orca_forks() {
+        assert_eq!(
+            OrcaFork::identify("; generated by OrcaSlicer 1.5.0 on 2023-07-09 at 11:51:00"),
+            Some(OrcaFork::OrcaSlicer)
+        );
+        assert_eq!(
+            OrcaFork::identify(
+                "; generated by CrealityPrint 5.1.6.10470 on 2024-05-01 at 12:00:00"
+            ),
+            Some(OrcaFork::CrealityPrint)
+        );
+        assert_eq!(
+            OrcaFork::identify("; generated by ElegooSlicer 1.1.0 on 2025-01-12 at 09:12:44"),
+            Some(OrcaFork::ElegooSlicer)
+        );
+        assert_eq!(
+            OrcaFork::identify("; generated by QIDIStudio 1.1.3 on 2024-11-02 at 18:03:10"),
+            Some(OrcaFork::QidiStudio)
+        );
+        assert_eq!(
+            OrcaFork::identify("; generated by Snapmaker_Orca 2.0.1 on 2025-02-20 at 10:01:31"),
+            Some(OrcaFork::SnapmakerOrca)
+        );
+        assert_eq!(
+            OrcaFork::identify("; generated by PrusaSlicer 2.6.1 on 2023-09-01 at 10:00:00"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_orca_forks() {
+        let input = concat!(
+            "M83\n",
+            ";TYPE:Outer wall\n",
+            "G1 X10 Y10 E.5\n",
+            ";TYPE:Sparse infill\n",
+            "G1 X20 Y10 E.5\n",
+        );
+
+        for fork in <OrcaFork as strum::IntoEnumIterator>::iter() {
+            let processor = OrcaSlicerProcessor::new(fork, None);
+            let result: String = processor
+                .process(std::io::Cursor::new(input.as_bytes()), &CONFIG)
+                .collect();
+            let result: Vec<&str> = result.split('\n').collect();
+            let control_stmnts: Vec<&str> = result
+                .iter()
+                .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+                .map(|line| line.rsplit("; ").next().unwrap())
+                .collect();
+            assert_eq!(
+                control_stmnts,
+                vec!["TYPE:External perimeter", "TYPE:Internal infill"],
+                "{fork}"
+            );
+            assert!(
+                result.contains(&format!("; Identified slicer: {fork}").as_str()),
+                "{fork}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_orcaslicer_versioned_markers() {
+        let input = ";TYPE:Internal Bridge\nG1 X10 Y10 E.5\n";
+
+        for (version, expected) in [(Version::new(1, 9, 0), 0), (Version::new(2, 0, 0), 1)] {
+            let processor = OrcaSlicerProcessor::new(OrcaFork::OrcaSlicer, Some(version));
+            let result: String = processor
+                .process(std::io::Cursor::new(input.as_bytes()), &CONFIG)
+                .collect();
+            assert_eq!(
+                result
+                    .split('\n')
+                    .filter(|l| l.starts_with("SET_VELOCITY_LIMIT"))
+                    .filter(|l| l.ends_with("; TYPE:Internal bridge infill"))
+                    .count(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_orcaslicer_header_version() {
+        let input = ";TYPE:Internal Bridge\nG1 X10 Y10 E.5\n";
+
+        for (header, expected) in [
+            (
+                "; generated by OrcaSlicer 1.9.1 on 2024-03-02 at 10:21:43",
+                0,
+            ),
+            (
+                "; generated by OrcaSlicer 2.1.1 on 2024-08-12 at 19:05:12",
+                1,
+            ),
+        ] {
+            let processor = identify_slicer_marker(header, &[]).unwrap();
+            let result: String = processor
+                .process(std::io::Cursor::new(input.as_bytes()), &CONFIG)
+                .collect();
+            assert_eq!(
+                result
+                    .split('\n')
+                    .filter(|l| l.starts_with("SET_VELOCITY_LIMIT"))
+                    .filter(|l| l.ends_with("; TYPE:Internal bridge infill"))
+                    .count(),
+                expected,
+                "{header}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_orcaslicer_feature_vocabulary() {
+        let processor = OrcaSlicerProcessor::new(OrcaFork::OrcaSlicer, Some(Version::new(2, 1, 1)));
+        let input = File::open(GCODE_PATH.join("orcaslicer-2.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            0
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            12
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Brim"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Prime tower"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support transition"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Floating vertical shell"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal bridge infill"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Ironing"))
+                .count(),
+            2
+        );
+
+        // Markers directly followed by a travel are coalesced into the travel, the mapping
+        // itself is checked against the markers of the file
+        for (feature_type, marker) in [
+            (FeatureType::Brim, ";TYPE:Brim"),
+            (FeatureType::PrimeTower, ";TYPE:Prime tower"),
+            (FeatureType::SupportTransition, ";TYPE:Support transition"),
+            (
+                FeatureType::FloatingVerticalShell,
+                ";TYPE:Floating vertical shell",
+            ),
+            (FeatureType::InternalBridgeInfill, ";TYPE:Internal Bridge"),
+            (FeatureType::Ironing, ";TYPE:Ironing"),
+        ] {
+            assert!(result.contains(&marker), "{marker}");
+            assert_eq!(OrcaSlicerProcessor::as_marker(&feature_type), marker);
+        }
+    }
 }
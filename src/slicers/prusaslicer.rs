@@ -1,12 +1,54 @@
+use crate::slicers::slic3r::Dialect;
 use crate::slicers::{slic3r, AccelerationPreProcessor};
-use crate::types::{AccelerationSettings, FeatureType};
+use crate::types::{Config, FeatureType, Version};
 use std::io::{Read, Seek};
+use std::ops::Range;
 
-pub(crate) struct PrusaSlicerProcessor {}
+pub(crate) struct PrusaSlicerProcessor {
+    version: Option<Version>,
+}
 
 impl PrusaSlicerProcessor {
-    pub fn new() -> Self {
-        Self {}
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> =
+        Version::new(2, 0, 0)..Version::new(2, 10, 0);
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in PrusaSlicer
+            FeatureType::FirstLayer => ";TYPE:First layer",
+            FeatureType::SmallPerimeter => ";TYPE:Small perimeter",
+            FeatureType::Travel => ";TYPE:Travel",
+            FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
+            FeatureType::Brim => ";TYPE:Brim",
+            FeatureType::Milling => ";TYPE:Milling",
+            FeatureType::SupportTransition => ";TYPE:Support transition",
+            FeatureType::FloatingVerticalShell => ";TYPE:Floating vertical shell",
+            FeatureType::BottomSurface => ";TYPE:Bottom surface",
+
+            // Supported feature types
+            FeatureType::Custom => ";TYPE:Custom",
+            FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+            FeatureType::OverhangPerimeter => ";TYPE:Overhang perimeter",
+            FeatureType::InternalPerimeter => ";TYPE:Perimeter",
+            FeatureType::TopSolidInfill => ";TYPE:Top solid infill",
+            FeatureType::SolidInfill => ";TYPE:Solid infill",
+            FeatureType::InternalInfill => ";TYPE:Internal infill",
+            FeatureType::BridgeInfill => ";TYPE:Bridge infill",
+            FeatureType::ThinWall => ";TYPE:Thin wall",
+            FeatureType::GapFill => ";TYPE:Gap fill",
+            // Skirt and brim share a single role
+            FeatureType::Skirt => ";TYPE:Skirt/Brim",
+            FeatureType::Ironing => ";TYPE:Ironing",
+            FeatureType::PrimeTower => ";TYPE:Wipe tower",
+            FeatureType::SupportMaterial => ";TYPE:Support material",
+            FeatureType::SupportMaterialInterface => ";TYPE:Support material interface",
+            FeatureType::Wipe => ";WIPE_START",
+        }
     }
 }
 
@@ -14,48 +56,28 @@ impl AccelerationPreProcessor for PrusaSlicerProcessor {
     fn process<'a>(
         &'a self,
         input: impl Read + Seek + Send + 'a,
-        settings: &'a AccelerationSettings,
+        config: &'a Config,
     ) -> generator::Generator<'a, (), String> {
-        let as_marker: fn(&FeatureType) -> &str = |feature_type: &FeatureType| {
-            match feature_type {
-                // Not implemented in PrusaSlicer
-                FeatureType::FirstLayer => ";TYPE:First layer",
-                FeatureType::Travel => ";TYPE:Travel",
-                FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
-
-                // Supported feature types
-                FeatureType::Custom => ";TYPE:Custom",
-                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
-                FeatureType::OverhangPerimeter => ";TYPE:Overhang perimeter",
-                FeatureType::InternalPerimeter => ";TYPE:Perimeter",
-                FeatureType::TopSolidInfill => ";TYPE:Top solid infill",
-                FeatureType::SolidInfill => ";TYPE:Solid infill",
-                FeatureType::InternalInfill => ";TYPE:Internal infill",
-                FeatureType::BridgeInfill => ";TYPE:Bridge infill",
-                FeatureType::ThinWall => ";TYPE:Thin wall",
-                FeatureType::GapFill => ";TYPE:Gap fill",
-                FeatureType::Skirt => ";TYPE:Skirt/Brim",
-                FeatureType::SupportMaterial => ";TYPE:Support material",
-                FeatureType::SupportMaterialInterface => ";TYPE:Support material interface",
-            }
-        };
-
-        slic3r::process(input, settings, as_marker)
+        slic3r::process(
+            input,
+            config,
+            Dialect::new("PrusaSlicer", self.version, Self::as_marker),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::slicers::tests::{GCODE_PATH, SETTINGS};
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
     use std::fs::File;
 
     #[test]
     fn test_prusaslicer() {
-        let processor = PrusaSlicerProcessor::new();
+        let processor = PrusaSlicerProcessor::new(Some(Version::new(2, 4, 0)));
         let input = File::open(GCODE_PATH.join("prusaslicer.gcode")).unwrap();
 
-        let result: String = processor.process(input, &SETTINGS).collect();
+        let result: String = processor.process(input, &CONFIG).collect();
         let result: Vec<&str> = result.split('\n').collect();
         let control_stmnts: Vec<&str> = result
             .iter()
@@ -75,28 +97,28 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Top solid infill"))
                 .count(),
-            7
+            4
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Travel"))
                 .count(),
-            596
+            594
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Solid infill"))
                 .count(),
-            86
+            45
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Internal infill"))
                 .count(),
-            255
+            191
         );
         assert_eq!(
             control_stmnts
@@ -117,14 +139,50 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:External perimeter"))
                 .count(),
-            127
+            119
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Skirt"))
                 .count(),
-            12
+            11
+        );
+    }
+
+    #[test]
+    fn test_prusaslicer_roles() {
+        let processor = PrusaSlicerProcessor::new(Some(Version::new(2, 7, 0)));
+        let input = "M83\n;TYPE:Skirt/Brim\nG1 X10 Y10 E.5\n;TYPE:Wipe tower\nG1 X20 Y20 E.5\n;TYPE:Ironing\nG1 X30 Y30 E.05\n";
+
+        let result: String = processor
+            .process(std::io::Cursor::new(input.as_bytes()), &CONFIG)
+            .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Prime tower"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Ironing"))
+                .count(),
+            1
         );
     }
 }
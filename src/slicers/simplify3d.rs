@@ -0,0 +1,180 @@
+use crate::slicers::slic3r::{Dialect, LayerChange, Markers};
+use crate::slicers::{slic3r, AccelerationPreProcessor};
+use crate::types::{Config, FeatureType, TravelStyle, Version};
+
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+pub(crate) struct Simplify3DProcessor {
+    version: Option<Version>,
+}
+
+impl Simplify3DProcessor {
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> = Version::new(4, 0, 0)..Version::new(5, 2, 0);
+
+    /// Layers are announced as `; layer 1, Z = 0.200`, the end of the print as `; layer end`
+    pub(crate) const LAYER_CHANGE: LayerChange<'static> = LayerChange::Numbered("; layer ");
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in Simplify3D
+            FeatureType::FirstLayer => "; feature first layer",
+            FeatureType::SmallPerimeter => "; feature small perimeter",
+            FeatureType::Travel => "; feature travel",
+            FeatureType::Custom => "; feature custom",
+            FeatureType::OverhangPerimeter => "; feature overhang perimeter",
+            FeatureType::TopSolidInfill => "; feature top solid layer",
+            FeatureType::InternalBridgeInfill => "; feature internal bridge",
+            FeatureType::ThinWall => "; feature thin wall",
+            FeatureType::Brim => "; feature brim",
+            FeatureType::Ironing => "; feature ironing",
+            FeatureType::SupportTransition => "; feature support transition",
+            FeatureType::FloatingVerticalShell => "; feature floating vertical shell",
+            FeatureType::BottomSurface => "; feature bottom surface",
+            FeatureType::Wipe => "; feature wipe",
+            FeatureType::Milling => "; feature milling",
+
+            // Supported feature types
+            FeatureType::ExternalPerimeter => "; feature outer perimeter",
+            FeatureType::InternalPerimeter => "; feature inner perimeter",
+            FeatureType::SolidInfill => "; feature solid layer",
+            FeatureType::InternalInfill => "; feature infill",
+            FeatureType::BridgeInfill => "; feature bridge",
+            FeatureType::GapFill => "; feature gap fill",
+            FeatureType::Skirt => "; feature skirt",
+            FeatureType::SupportMaterial => "; feature support",
+            FeatureType::SupportMaterialInterface => "; feature dense support",
+            FeatureType::PrimeTower => "; feature prime pillar",
+        }
+    }
+}
+
+impl AccelerationPreProcessor for Simplify3DProcessor /**/ {
+    fn process<'a>(
+        &'a self,
+        input: impl Read + Seek + Send + 'a,
+        config: &'a Config,
+    ) -> generator::Generator<'a, (), String> {
+        let dialect = Dialect {
+            version: self.version,
+            slicer: "Simplify3D",
+            layer_change: Self::LAYER_CHANGE,
+            travel: TravelStyle::G1,
+            markers: Markers::Builtin(Self::as_marker),
+            wipe_end: None,
+        };
+
+        slic3r::process(input, config, dialect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+
+    #[test]
+    fn test_simplify3d() {
+        let processor = Simplify3DProcessor::new(Some(Version::new(4, 1, 2)));
+        let input = File::open(GCODE_PATH.join("simplify3d.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            34
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:External perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Solid infill"))
+                .count(),
+            16
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Gap fill"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material interface"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_simplify3d_layers() {
+        let input = BufReader::new(File::open(GCODE_PATH.join("simplify3d.gcode")).unwrap());
+
+        let layers = input
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| Simplify3DProcessor::LAYER_CHANGE.matches(line.trim()))
+            .count();
+        assert_eq!(layers, 3);
+        assert!(!Simplify3DProcessor::LAYER_CHANGE.matches("; layer end"));
+    }
+}
@@ -1,50 +1,275 @@
-use crate::gcode::{dump_settings, dump_stats, set_velocity_limit};
+//! Common processing functionality for slic3r forks
+
+use crate::gcode::{
+    dump_settings, dump_slicer, dump_stats, Command, MachineState, MoveKind, VelocityLimits,
+};
 use crate::types::{
-    AccelerationSettings, AccelerationType, FeatureType, DEFAULT_FIRST_LAYER_ACCELERATION,
-    DEFAULT_TRAVEL_ACCELERATION,
+    AccelerationType, Config, DialectConfig, FeatureType, TravelStyle, Version,
+    DEFAULT_FIRST_LAYER_ACCELERATION, DEFAULT_TRAVEL_ACCELERATION,
 };
-use counter::Counter;
 use generator::{done, Gn};
-use once_cell::sync::Lazy;
-use regex::Regex;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read, Seek};
+use strum::IntoEnumIterator;
+
+/// Layer change comment used by PrusaSlicer, SuperSlicer and OrcaSlicer
+pub(crate) const LAYER_CHANGE_MARKER: &str = ";LAYER_CHANGE";
+
+/// End of a block of wipe moves used by PrusaSlicer, SuperSlicer and OrcaSlicer, the start is
+/// marked by the `Wipe` feature marker
+pub(crate) const WIPE_END_MARKER: &str = ";WIPE_END";
+
+/// How a slic3r fork marks the start of a new layer in its G-Code output
+#[derive(Copy, Clone)]
+pub(crate) enum LayerChange<'a> {
+    /// A comment starting with the given marker is emitted at the start of every layer
+    Marker(&'a str),
+    /// A comment starting with the given marker followed by the layer number is emitted at
+    /// the start of every layer, other comments with the same prefix are ignored
+    Numbered(&'a str),
+    /// No comment is emitted, a layer starts with the first extrusion at a new Z height
+    ZHeight,
+}
+
+impl LayerChange<'_> {
+    /// Checks if a line is the comment starting a new layer
+    pub(crate) fn matches(&self, line: &str) -> bool {
+        match self {
+            LayerChange::Marker(marker) => line.starts_with(marker),
+            LayerChange::Numbered(marker) => line
+                .strip_prefix(marker)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit())),
+            LayerChange::ZHeight => false,
+        }
+    }
+}
 
-/// Common processing functionality for slic3r forks
+/// Lookup table for the comments that start a block of print moves for a feature
+#[derive(Copy, Clone)]
+pub(crate) enum Markers<'a> {
+    /// Built-in table mapping a feature type to its marker
+    Builtin(fn(&FeatureType) -> &str),
+    /// User-defined table mapping markers to feature types
+    Table(&'a HashMap<String, FeatureType>),
+}
 
-static TRAVEL_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"(?i)^G1\s+X[\d.]+\s+Y[\d.]+(?<feedrate>\s+F[\d.]+)?\s*(;|$)"#).unwrap()
-});
+/// Comments a slic3r fork uses to annotate layers and features in its G-Code output
+#[derive(Copy, Clone)]
+pub(crate) struct Dialect<'a> {
+    /// Name of the slicer, used for reporting
+    pub(crate) slicer: &'a str,
+    /// Version of the slicer, used for reporting
+    pub(crate) version: Option<Version>,
+    /// Start of a new layer
+    pub(crate) layer_change: LayerChange<'a>,
+    /// G-Code command style used for travel moves
+    pub(crate) travel: TravelStyle,
+    /// Comments that start a block of print moves
+    pub(crate) markers: Markers<'a>,
+    /// Comment marking the end of a block of wipe moves
+    pub(crate) wipe_end: Option<&'a str>,
+}
+
+impl<'a> Dialect<'a> {
+    /// Dialect for slicers using `;LAYER_CHANGE` and `;TYPE:` comments
+    pub(crate) fn new(
+        slicer: &'a str,
+        version: Option<Version>,
+        as_marker: fn(&FeatureType) -> &str,
+    ) -> Self {
+        Self {
+            slicer,
+            version,
+            layer_change: LayerChange::Marker(LAYER_CHANGE_MARKER),
+            travel: TravelStyle::G1,
+            markers: Markers::Builtin(as_marker),
+            wipe_end: Some(WIPE_END_MARKER),
+        }
+    }
+
+    /// Returns the feature type for a line if it is a feature marker
+    fn feature_type(&self, line: &str) -> Option<FeatureType> {
+        match self.markers {
+            Markers::Builtin(as_marker) => FeatureType::iter()
+                .filter(FeatureType::has_marker)
+                .find(|feature_type| as_marker(feature_type) == line),
+            Markers::Table(table) => table.get(line).copied().filter(FeatureType::has_marker),
+        }
+    }
+
+    /// Checks if a command is a travel move, given the kind of move it was classified as
+    fn is_travel(&self, command: &Command, move_kind: Option<MoveKind>) -> bool {
+        match self.travel {
+            TravelStyle::G1 => move_kind == Some(MoveKind::Travel),
+            TravelStyle::G0 => command.code == "G0",
+        }
+    }
+}
+
+impl<'a> From<&'a DialectConfig> for Dialect<'a> {
+    fn from(config: &'a DialectConfig) -> Self {
+        Self {
+            slicer: &config.name,
+            version: None,
+            layer_change: config
+                .layer_change
+                .as_deref()
+                .map_or(LayerChange::ZHeight, LayerChange::Marker),
+            travel: config.travel,
+            markers: Markers::Table(&config.markers),
+            wipe_end: config.wipe_end.as_deref(),
+        }
+    }
+}
+
+/// Lines of a perimeter loop held back until its length is known
+struct PerimeterLoop {
+    /// Feature type of the perimeter
+    feature_type: FeatureType,
+    /// Lines of the loop, starting with its first extrusion move, and the speed of the
+    /// extrusion moves among them
+    lines: Vec<(String, Option<f64>)>,
+    /// Length of the loop in the XY plane
+    length: f64,
+}
+
+impl PerimeterLoop {
+    /// Emits the lines of the loop with the small perimeter settings if it is below the
+    /// configured length, a loop following a small one goes back to the perimeter settings.
+    /// Speed ranges are applied to the extrusion moves of the loop as they are emitted.
+    fn finish<'a>(
+        self,
+        config: &Config,
+        layer_num: u64,
+        limits: &mut VelocityLimits,
+    ) -> generator::Generator<'a, (), String> {
+        tracing::trace!("Perimeter loop with a length of {:.2}mm", self.length);
+        let small = self.length < config.small_perimeter.max_length;
+        let speed = self.lines.first().and_then(|(_, speed)| *speed);
+        let feature_type = if small {
+            Some(FeatureType::SmallPerimeter)
+        } else if limits
+            .requested()
+            .is_some_and(|(label, _)| label == FeatureType::SmallPerimeter)
+        {
+            Some(self.feature_type)
+        } else {
+            None
+        };
+        if let Some((label, control)) =
+            feature_type.and_then(|feature_type| config.control(&feature_type, layer_num, speed))
+        {
+            limits.set(&label, &control);
+        }
+
+        let printing = match small {
+            true => FeatureType::SmallPerimeter,
+            false => self.feature_type,
+        };
+        let mut output = Vec::with_capacity(self.lines.len());
+        for (line, speed) in self.lines {
+            if speed.is_some() {
+                speed_changed(config, layer_num, Some(printing), speed, limits);
+            }
+            if let Some(limit) = limits.flush() {
+                output.extend(limit);
+            }
+            output.push(line);
+        }
+
+        Gn::new_scoped(move |mut s| {
+            for line in output {
+                s.yield_with(line);
+            }
+
+            done!()
+        })
+    }
+}
+
+/// Lines of a travel held back until its length is known, from its first travel move up to
+/// the next print move. Slicers often split a travel into short segments, e.g. to avoid
+/// crossing perimeters, so the length of all of them is added up.
+pub(crate) struct TravelRun {
+    /// Lines of the travel, starting with its first travel move
+    lines: Vec<String>,
+    /// Length of the travel in the XY plane
+    length: f64,
+}
+
+impl TravelRun {
+    pub(crate) fn new(line: &str, length: f64) -> Self {
+        Self {
+            lines: vec![format!("{}\n", line)],
+            length,
+        }
+    }
+
+    /// Adds a line of the travel and the distance it moves
+    pub(crate) fn push(&mut self, line: &str, length: f64) {
+        self.lines.push(format!("{}\n", line));
+        self.length += length;
+    }
+
+    /// Emits the lines of the travel, switching to the travel settings first unless it is
+    /// shorter than the configured minimum distance. Returns whether the settings switched.
+    pub(crate) fn finish<'a>(
+        self,
+        config: &Config,
+        limits: &mut VelocityLimits,
+    ) -> (bool, generator::Generator<'a, (), String>) {
+        tracing::trace!("Travel with a length of {:.2}mm", self.length);
+        // Short travels keep the print acceleration instead of toggling back and forth
+        let switched = self.length >= config.travel.min_distance;
+        if switched {
+            limits.set(
+                &FeatureType::Travel,
+                config
+                    .features
+                    .get(&FeatureType::Travel)
+                    .unwrap_or(&DEFAULT_TRAVEL_ACCELERATION),
+            );
+        }
+
+        let limit = limits.flush();
+        let generator = Gn::new_scoped(move |mut s| {
+            if let Some(limit) = limit {
+                s.yield_from(limit);
+            }
+            for line in self.lines {
+                s.yield_with(line);
+            }
+
+            done!()
+        });
+        (switched, generator)
+    }
+}
 
 pub(crate) fn process<'a>(
     input: impl Read + Seek + Send + 'a,
-    settings: &'a AccelerationSettings,
-    as_marker: fn(&FeatureType) -> &str,
+    config: &'a Config,
+    dialect: Dialect<'a>,
 ) -> generator::Generator<'a, (), String> {
     let mut input = BufReader::new(input);
 
     let mut layer_num: u64 = 0;
-    let mut beancounter: Counter<FeatureType, u64> = Counter::new();
+    let mut limits = VelocityLimits::new(config.firmware());
     let mut last_set_acceleration_type: AccelerationType = AccelerationType::None;
     let mut current_feature_type: Option<FeatureType> = None;
+    let mut wiped_feature_type: Option<FeatureType> = None;
+    let mut machine = MachineState::default();
+    let mut layer_z: Option<f64> = None;
+    let mut perimeter_loop: Option<PerimeterLoop> = None;
+    let mut travel_run: Option<TravelRun> = None;
+    let detect_small_perimeters = config.small_perimeter.max_length > 0.0
+        && config.features.contains_key(&FeatureType::SmallPerimeter);
 
     Gn::new_scoped_opt(0x8000, move |mut s| {
-        'lines: for line in input.by_ref().lines() {
+        for line in input.by_ref().lines() {
             let line = line.unwrap_or("".to_string());
 
-            if line.trim().starts_with(";LAYER_CHANGE") {
-                layer_num += 1;
-                s.yield_with(format!("{}\n", &line));
-
-                if layer_num == 1 {
-                    let control = settings
-                        .get(&FeatureType::FirstLayer)
-                        .unwrap_or(&DEFAULT_FIRST_LAYER_ACCELERATION);
-                    s.yield_from(set_velocity_limit(&FeatureType::FirstLayer, control));
-                    beancounter[&FeatureType::FirstLayer] += 1;
-                }
-
-                continue;
-            }
+            let starts_layer = dialect.layer_change.matches(line.trim());
 
             if line.trim().starts_with("M204 S") {
                 tracing::trace!(line, "Skipping Marlin Set Starting Acceleration command");
@@ -56,63 +281,275 @@ pub(crate) fn process<'a>(
                 continue;
             }
 
-            for (feature_type, control) in settings {
-                if line.trim() == as_marker(feature_type) {
-                    tracing::trace!("Detected feature type {}", feature_type);
-                    current_feature_type = Some(*feature_type);
-                    s.yield_(format!("{}\n", line));
-                    s.yield_from(set_velocity_limit(feature_type, control));
-                    beancounter[feature_type] += 1;
-                    last_set_acceleration_type = AccelerationType::Print;
+            if config.marlin.is_some()
+                && Command::parse(line.trim())
+                    .is_some_and(|c| matches!(c.code.as_str(), "M204" | "M205"))
+            {
+                tracing::trace!(line, "Skipping Marlin acceleration and jerk command");
+                continue;
+            }
 
-                    continue 'lines;
+            // A perimeter loop continues until the first move that isn't an extrusion or
+            // the next layer or feature comment
+            if let Some(mut pending) = perimeter_loop.take() {
+                let mut next = machine;
+                let command = Command::parse(line.trim());
+                let move_kind = command.as_ref().and_then(|c| next.update(c));
+                if !starts_layer
+                    && dialect.feature_type(line.trim()).is_none()
+                    && dialect.wipe_end != Some(line.trim())
+                    && matches!(move_kind, None | Some(MoveKind::Extrusion))
+                {
+                    pending.length += command
+                        .as_ref()
+                        .map_or(0.0, |c| next.xy_path_length(machine.position, c));
+                    let speed = move_kind.and_then(|_| next.speed());
+                    pending.lines.push((format!("{}\n", &line), speed));
+                    machine = next;
+                    perimeter_loop = Some(pending);
+                    continue;
                 }
+
+                s.yield_from(pending.finish(config, layer_num, &mut limits));
             }
 
-            if TRAVEL_REGEX.is_match(&line) {
-                if last_set_acceleration_type != AccelerationType::Travel {
-                    s.yield_from(set_velocity_limit(
-                        &FeatureType::Travel,
-                        settings
-                            .get(&FeatureType::Travel)
-                            .unwrap_or(&DEFAULT_TRAVEL_ACCELERATION),
-                    ));
-                    beancounter[&FeatureType::Travel] += 1;
+            // A travel continues with retractions, Z-hops and further travel moves until the
+            // first print move or the next layer or feature comment
+            if let Some(mut pending) = travel_run.take() {
+                let mut next = machine;
+                let command = Command::parse(line.trim());
+                let move_kind = command.as_ref().and_then(|c| next.update(c));
+                if !starts_layer
+                    && dialect.feature_type(line.trim()).is_none()
+                    && dialect.wipe_end != Some(line.trim())
+                    && !move_kind.is_some_and(|kind| kind.is_printing())
+                {
+                    let length = match command {
+                        Some(ref c) if dialect.is_travel(c, move_kind) => {
+                            next.xy_path_length(machine.position, c)
+                        }
+                        _ => 0.0,
+                    };
+                    pending.push(&line, length);
+                    machine = next;
+                    travel_run = Some(pending);
+                    continue;
+                }
+
+                let (switched, lines) = pending.finish(config, &mut limits);
+                if switched {
                     last_set_acceleration_type = AccelerationType::Travel;
                 }
+                s.yield_from(lines);
+            }
+
+            if starts_layer {
+                layer_num += 1;
+                s.yield_with(format!("{}\n", &line));
+                layer_changed(
+                    config,
+                    layer_num,
+                    current_feature_type
+                        .filter(|_| last_set_acceleration_type == AccelerationType::Print),
+                    machine.speed(),
+                    &mut limits,
+                );
+
+                continue;
+            }
+
+            if let Some(feature_type) = dialect
+                .feature_type(line.trim())
+                .filter(|feature_type| config.has_settings(feature_type))
+            {
+                tracing::trace!("Detected feature type {}", feature_type);
+                if feature_type == FeatureType::Wipe {
+                    wiped_feature_type = current_feature_type;
+                }
+                current_feature_type = Some(feature_type);
+                s.yield_(format!("{}\n", line));
+                // Features with speed ranges only are set with their first print move
+                if let Some((label, control)) =
+                    config.control(&feature_type, layer_num, machine.speed())
+                {
+                    limits.set(&label, &control);
+                }
+                last_set_acceleration_type = AccelerationType::Print;
+
+                continue;
+            }
+
+            // The wipe limit stays active until the next print move of the wiped feature
+            if dialect.wipe_end == Some(line.trim())
+                && current_feature_type == Some(FeatureType::Wipe)
+            {
+                current_feature_type = wiped_feature_type.take();
+                if last_set_acceleration_type == AccelerationType::Print {
+                    last_set_acceleration_type = AccelerationType::Wipe;
+                }
+            }
 
+            let command = Command::parse(line.trim());
+            let start = machine.position;
+            let move_kind = command.as_ref().and_then(|c| machine.update(c));
+            if command
+                .as_ref()
+                .is_some_and(|c| dialect.is_travel(c, move_kind))
+            {
+                if last_set_acceleration_type != AccelerationType::Travel {
+                    let length = command
+                        .as_ref()
+                        .map_or(0.0, |c| machine.xy_path_length(start, c));
+                    travel_run = Some(TravelRun::new(&line, length));
+                    continue;
+                }
+
+                if let Some(limit) = limits.flush() {
+                    s.yield_from(limit);
+                }
                 s.yield_with(format!("{}\n", &line));
                 continue;
-            } else if last_set_acceleration_type == AccelerationType::Travel {
-                if let Some(ref feature_type) = current_feature_type {
-                    if let Some(control) = settings.get(feature_type) {
-                        s.yield_from(set_velocity_limit(feature_type, control));
-                        beancounter[feature_type] += 1;
-                        last_set_acceleration_type = AccelerationType::Print;
-                    }
+            } else if matches!(
+                last_set_acceleration_type,
+                AccelerationType::Travel | AccelerationType::Wipe
+            ) && move_kind.is_some_and(|kind| kind.is_printing())
+            {
+                if let Some((label, control)) = current_feature_type.and_then(|feature_type| {
+                    config.control(&feature_type, layer_num, machine.speed())
+                }) {
+                    limits.set(&label, &control);
+                    last_set_acceleration_type = AccelerationType::Print;
+                }
+            }
+
+            if last_set_acceleration_type == AccelerationType::Print
+                && move_kind.is_some_and(|kind| kind.is_printing())
+            {
+                speed_changed(
+                    config,
+                    layer_num,
+                    current_feature_type,
+                    machine.speed(),
+                    &mut limits,
+                );
+            }
+
+            if let LayerChange::ZHeight = dialect.layer_change {
+                let current_z = machine.position[2];
+                if move_kind == Some(MoveKind::Extrusion) && layer_z.is_none_or(|z| current_z > z) {
+                    layer_z = Some(current_z);
+                    layer_num += 1;
+                    layer_changed(
+                        config,
+                        layer_num,
+                        current_feature_type
+                            .filter(|_| last_set_acceleration_type == AccelerationType::Print),
+                        machine.speed(),
+                        &mut limits,
+                    );
+                }
+            }
+
+            if detect_small_perimeters && move_kind == Some(MoveKind::Extrusion) {
+                if let Some(feature_type) = current_feature_type.filter(FeatureType::is_perimeter) {
+                    perimeter_loop = Some(PerimeterLoop {
+                        feature_type,
+                        lines: vec![(format!("{}\n", &line), machine.speed())],
+                        length: command
+                            .as_ref()
+                            .map_or(0.0, |c| machine.xy_path_length(start, c)),
+                    });
+                    continue;
+                }
+            }
+
+            // Changes without motion in between are coalesced into the last one
+            if move_kind.is_some() {
+                if let Some(limit) = limits.flush() {
+                    s.yield_from(limit);
                 }
             }
             s.yield_with(format!("{}\n", &line));
         }
 
-        s.yield_from(dump_settings(settings));
-        s.yield_from(dump_stats(&beancounter));
+        if let Some(pending) = perimeter_loop.take() {
+            s.yield_from(pending.finish(config, layer_num, &mut limits));
+        }
+        if let Some(pending) = travel_run.take() {
+            s.yield_from(pending.finish(config, &mut limits).1);
+        }
+
+        s.yield_from(dump_slicer(dialect.slicer, dialect.version));
+        s.yield_from(dump_settings(config));
+        s.yield_from(dump_stats(&limits));
 
         done!();
     })
 }
 
+/// Sets the acceleration control for the start of a layer, the first layer starts with the
+/// first layer settings. On the following layers the feature still being printed is updated
+/// as the first layer settings fade out.
+pub(crate) fn layer_changed(
+    config: &Config,
+    layer_num: u64,
+    printing: Option<FeatureType>,
+    speed: Option<f64>,
+    limits: &mut VelocityLimits,
+) {
+    if layer_num == 1 {
+        let control = config
+            .features
+            .get(&FeatureType::FirstLayer)
+            .unwrap_or(&DEFAULT_FIRST_LAYER_ACCELERATION);
+        limits.set(&FeatureType::FirstLayer, control);
+    } else if layer_num <= config.first_layer.layers + 1 {
+        if let Some((label, control)) =
+            printing.and_then(|feature_type| config.control(&feature_type, layer_num, speed))
+        {
+            limits.set(&label, &control);
+        }
+    }
+}
+
+/// Sets the acceleration control for the speed of a print move if the feature being printed
+/// has speed ranges and the speed range changed.
+pub(crate) fn speed_changed(
+    config: &Config,
+    layer_num: u64,
+    printing: Option<FeatureType>,
+    speed: Option<f64>,
+    limits: &mut VelocityLimits,
+) {
+    if let Some((label, control)) = printing
+        .filter(|feature_type| config.speeds.contains_key(feature_type))
+        .and_then(|feature_type| config.control(&feature_type, layer_num, speed))
+    {
+        if limits.requested() != Some((label, control)) {
+            limits.set(&label, &control);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::slicers::tests::SETTINGS;
+    use crate::slicers::tests::CONFIG;
+    use crate::types::{
+        AccelerationControl, FirstLayerConfig, FirstLayerMode, SmallPerimeterConfig, TravelConfig,
+    };
     use std::io::Cursor;
 
     #[test]
     fn test_m204_removal() {
         let input = Cursor::new("M204 S12000\n".as_bytes());
 
-        let result: String = process(input, &SETTINGS, |_ft: &FeatureType| "TESTING").collect();
+        let result: String = process(
+            input,
+            &CONFIG,
+            Dialect::new("Testing", None, |_ft: &FeatureType| "TESTING"),
+        )
+        .collect();
         let result: Vec<&str> = result.split('\n').collect();
         assert_eq!(
             result
@@ -127,7 +564,12 @@ mod tests {
     fn test_set_velocity_limit_removal() {
         let input = Cursor::new("SET_VELOCITY_LIMIT SQUARE_CORNER_VELOCITY=13\n".as_bytes());
 
-        let result: String = process(input, &SETTINGS, |_ft: &FeatureType| "TESTING").collect();
+        let result: String = process(
+            input,
+            &CONFIG,
+            Dialect::new("Testing", None, |_ft: &FeatureType| "TESTING"),
+        )
+        .collect();
         let result: Vec<&str> = result.split('\n').collect();
         assert_eq!(
             result
@@ -137,4 +579,639 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn test_first_layer_modes() {
+        let input = format!(
+            "M83\n{}",
+            ";LAYER_CHANGE\n;TYPE:Internal infill\nG1 X10 Y10 E.5\n".repeat(3)
+        );
+        let as_marker = |ft: &FeatureType| match ft {
+            FeatureType::InternalInfill => ";TYPE:Internal infill",
+            _ => "TESTING",
+        };
+
+        for (mode, expected) in [
+            (
+                FirstLayerMode::Replace,
+                vec![
+                    "ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 ; TYPE:First Layer",
+                    "ACCEL=6000 ACCEL_TO_DECEL=3000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                ],
+            ),
+            (
+                FirstLayerMode::Cap,
+                vec![
+                    "ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                    "ACCEL=6000 ACCEL_TO_DECEL=3000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                ],
+            ),
+            (
+                FirstLayerMode::Ramp,
+                vec![
+                    "ACCEL=2000 ACCEL_TO_DECEL=1000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                    "ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                    "ACCEL=6000 ACCEL_TO_DECEL=3000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                ],
+            ),
+        ] {
+            let config = Config {
+                first_layer: FirstLayerConfig { layers: 2, mode },
+                ..CONFIG.clone()
+            };
+            let result: String = process(
+                Cursor::new(input.as_bytes()),
+                &config,
+                Dialect::new("Testing", None, as_marker),
+            )
+            .collect();
+            let control_stmnts: Vec<&str> = result
+                .split('\n')
+                .filter_map(|line| line.strip_prefix("SET_VELOCITY_LIMIT "))
+                .collect();
+            assert_eq!(control_stmnts, expected, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_travel_detection() {
+        let dialect = Dialect::new("Testing", None, |_ft: &FeatureType| "TESTING");
+        let is_travel = |line: &str| {
+            let mut machine = MachineState::default();
+            machine.update(&Command::parse("M83").unwrap());
+            let command = Command::parse(line).unwrap();
+            let move_kind = machine.update(&command);
+            dialect.is_travel(&command, move_kind)
+        };
+
+        for line in [
+            "G1 X10.5 Y20 F7800",
+            "G1 X-10.5 Y-20.25",
+            "G0 X10 Y20",
+            "G1 X10 Y20 Z.6 F7800",
+            "G1 F7800 Y20 X10",
+            "N120 G1 X10 Y20*51",
+            "G1 X10 Y20 E0",
+            "G2 X10 Y20 I5 J5",
+            "G3 I5 J0",
+        ] {
+            assert!(is_travel(line), "{line}");
+        }
+        for line in [
+            "G1 X10 Y20 E.5",
+            "G1 Z.6 F720",
+            "G1 E-.8 F2100",
+            "G1 X10 Y20 E-.2",
+            "G2 X10 Y20 I5 J5 E.5",
+            "G3 I5 J0 E1.2",
+        ] {
+            assert!(!is_travel(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_travel_block() {
+        let input = Cursor::new(
+            concat!(
+                "M83\n",
+                ";TYPE:Internal infill\n",
+                "G1 X10 Y10 E.5\n",
+                "G1 E-.8 F2100\n",
+                "G1 Z.6 F720\n",
+                "G1 X50 Y50 F12000\n",
+                "G1 X60 Y50 Z.6\n",
+                "G1 Z.2 F720\n",
+                "G1 E.8 F2100\n",
+                "G1 F3000\n",
+                "G1 X70 Y50 E.5\n",
+            )
+            .as_bytes(),
+        );
+
+        let result: String = process(
+            input,
+            &CONFIG,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::InternalInfill => ";TYPE:Internal infill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:Internal infill",
+                "TYPE:Travel",
+                "TYPE:Internal infill"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_travel_block_firmware_retraction() {
+        let input = Cursor::new(
+            concat!(
+                "M83\n",
+                ";TYPE:Internal infill\n",
+                "G1 X10 Y10 E.5\n",
+                "G10\n",
+                "G1 Z.6 F720\n",
+                "G1 X50 Y50 F12000\n",
+                "G1 X60 Y50 Z.6\n",
+                "G1 Z.2 F720\n",
+                "G11\n",
+                "G1 F3000\n",
+                "G1 X70 Y50 E.5\n",
+            )
+            .as_bytes(),
+        );
+
+        let result: String = process(
+            input,
+            &CONFIG,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::InternalInfill => ";TYPE:Internal infill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:Internal infill",
+                "TYPE:Travel",
+                "TYPE:Internal infill"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wipe() {
+        let input = Cursor::new(
+            concat!(
+                "M83\n",
+                ";TYPE:External perimeter\n",
+                "G1 X10 Y10 E.5\n",
+                ";WIPE_START\n",
+                "G1 X12 Y10 E-.3\n",
+                ";WIPE_END\n",
+                "G1 X14 Y10 E.5\n",
+                ";WIPE_START\n",
+                "G1 X16 Y10 E-.3\n",
+                ";WIPE_END\n",
+                "G1 E-.5\n",
+                "G1 X50 Y50\n",
+                "G1 E.8\n",
+                "G1 X60 Y50 E.5\n",
+            )
+            .as_bytes(),
+        );
+
+        let result: String = process(
+            input,
+            &CONFIG,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                FeatureType::Wipe => ";WIPE_START",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:External perimeter",
+                "TYPE:Wipe",
+                "TYPE:External perimeter",
+                "TYPE:Wipe",
+                "TYPE:Travel",
+                "TYPE:External perimeter"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redundant_limits() {
+        let input = Cursor::new(
+            concat!(
+                "M83\n",
+                ";TYPE:External perimeter\n",
+                "G1 X10 Y10 E.5\n",
+                ";TYPE:Ironing\n",
+                "G1 X20 Y10 E.5\n",
+                ";TYPE:Internal infill\n",
+                ";TYPE:External perimeter\n",
+                "G1 X30 Y10 E.5\n",
+            )
+            .as_bytes(),
+        );
+
+        // Ironing with the same settings as the external perimeter is redundant
+        let mut config = CONFIG.clone();
+        config.features.insert(
+            FeatureType::Ironing,
+            config.features[&FeatureType::ExternalPerimeter],
+        );
+
+        let result: String = process(
+            input,
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                FeatureType::Ironing => ";TYPE:Ironing",
+                FeatureType::InternalInfill => ";TYPE:Internal infill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(control_stmnts, vec!["TYPE:External perimeter"]);
+        assert!(result.contains(&"; Skipped redundant insertions       3"));
+    }
+
+    #[test]
+    fn test_short_travel() {
+        let input = concat!(
+            "M83\n",
+            ";TYPE:Gap fill\n",
+            "G1 X10 Y10 E.5\n",
+            "G1 X11 Y10\n",
+            "G1 X12 Y10 E.5\n",
+            "G1 X50 Y50\n",
+            "G1 X51 Y50 E.5\n",
+        );
+        let config = Config {
+            travel: TravelConfig { min_distance: 2.0 },
+            ..CONFIG.clone()
+        };
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::GapFill => ";TYPE:Gap fill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec!["TYPE:Gap fill", "TYPE:Travel", "TYPE:Gap fill"]
+        );
+    }
+
+    #[test]
+    fn test_segmented_travel() {
+        let input = format!(
+            "M83\n;TYPE:Gap fill\nG1 X10 Y10 E.5\nG1 E-.8\nG1 Z.6\n{}G1 Z.2\nG1 E.8\nG1 X39 Y10 E.5\n",
+            (1..=7)
+                .map(|i| format!("G1 X{} Y10\n", 10 + 4 * i))
+                .collect::<String>()
+        );
+        let config = Config {
+            travel: TravelConfig { min_distance: 5.0 },
+            ..CONFIG.clone()
+        };
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::GapFill => ";TYPE:Gap fill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec!["TYPE:Gap fill", "TYPE:Travel", "TYPE:Gap fill"]
+        );
+        let travel = result
+            .iter()
+            .position(|line| line.ends_with("; TYPE:Travel"))
+            .unwrap();
+        assert_eq!(result[travel + 1], "G1 X14 Y10");
+    }
+
+    #[test]
+    fn test_small_perimeter() {
+        let input = concat!(
+            "M83\n",
+            "G1 X10 Y10\n",
+            ";TYPE:External perimeter\n",
+            "G1 X12 Y10 E.1\n",
+            ";WIDTH:0.45\n",
+            "G1 X12 Y12 E.1\n",
+            "G1 X10 Y12 E.1\n",
+            "G1 X10 Y10 E.1\n",
+            "G1 X50 Y50\n",
+            "G1 X60 Y50 E.5\n",
+            "G1 X60 Y60 E.5\n",
+            "G1 X50 Y60 E.5\n",
+            "G1 X50 Y50 E.5\n",
+            "G1 X20 Y20\n",
+            "G1 X22 Y20 E.1\n",
+            "G1 X20 Y20 E.1\n",
+        );
+        let mut config = Config {
+            small_perimeter: SmallPerimeterConfig { max_length: 10.0 },
+            ..CONFIG.clone()
+        };
+        config.features.insert(
+            FeatureType::SmallPerimeter,
+            AccelerationControl {
+                accel: 1000,
+                accel_to_decel: Some(500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        assert_eq!(
+            result[..8],
+            [
+                "M83",
+                "SET_VELOCITY_LIMIT ACCEL=10000 ACCEL_TO_DECEL=10000 SQUARE_CORNER_VELOCITY=8 ; TYPE:Travel",
+                "G1 X10 Y10",
+                ";TYPE:External perimeter",
+                "SET_VELOCITY_LIMIT ACCEL=1000 ACCEL_TO_DECEL=500 SQUARE_CORNER_VELOCITY=5 ; TYPE:Small perimeter",
+                "G1 X12 Y10 E.1",
+                ";WIDTH:0.45",
+                "G1 X12 Y12 E.1",
+            ]
+        );
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:Travel",
+                "TYPE:Small perimeter",
+                "TYPE:Travel",
+                "TYPE:External perimeter",
+                "TYPE:Travel",
+                "TYPE:Small perimeter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_small_perimeter_arcs() {
+        let input = concat!(
+            "M83\n",
+            "G1 X100 Y100\n",
+            ";TYPE:External perimeter\n",
+            "G2 X100 Y100 I20 J0 E5\n",
+            "G1 X10 Y10\n",
+            "G2 X10 Y10 I1 J0 E.1\n",
+        );
+        let mut config = Config {
+            small_perimeter: SmallPerimeterConfig { max_length: 10.0 },
+            ..CONFIG.clone()
+        };
+        config.features.insert(
+            FeatureType::SmallPerimeter,
+            AccelerationControl {
+                accel: 1000,
+                accel_to_decel: Some(500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .map(|line| line.rsplit("; ").next().unwrap())
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "TYPE:Travel",
+                "TYPE:External perimeter",
+                "TYPE:Travel",
+                "TYPE:Small perimeter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_small_perimeter_speed_ranges() {
+        let input = concat!(
+            "M83\n",
+            "G1 X50 Y50\n",
+            ";TYPE:External perimeter\n",
+            "G1 X60 Y50 E.5 F1800\n",
+            "G1 X60 Y60 E.5\n",
+            "G1 X50 Y60 E.5 F6000\n",
+            "G1 X50 Y50 E.5\n",
+        );
+        let speeds: Config = toml::from_str(
+            r#"
+            [[speeds.ExternalPerimeter]]
+            max_speed = 50
+            accel = 1500
+            accel_to_decel = 750
+            scv = 5
+            "#,
+        )
+        .unwrap();
+        let mut config = Config {
+            small_perimeter: SmallPerimeterConfig { max_length: 10.0 },
+            speeds: speeds.speeds,
+            ..CONFIG.clone()
+        };
+        config.features.insert(
+            FeatureType::SmallPerimeter,
+            AccelerationControl {
+                accel: 1000,
+                accel_to_decel: Some(500),
+                minimum_cruise_ratio: None,
+                scv: 5,
+                velocity: None,
+            },
+        );
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        assert_eq!(
+            result[3..10],
+            [
+                ";TYPE:External perimeter",
+                "SET_VELOCITY_LIMIT ACCEL=1500 ACCEL_TO_DECEL=750 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                "G1 X60 Y50 E.5 F1800",
+                "G1 X60 Y60 E.5",
+                "SET_VELOCITY_LIMIT ACCEL=2200 ACCEL_TO_DECEL=1100 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                "G1 X50 Y60 E.5 F6000",
+                "G1 X50 Y50 E.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_speed_ranges_only() {
+        let input = concat!(
+            "M83\n",
+            ";TYPE:External perimeter\n",
+            "G1 X10 Y10 E.5 F1800\n",
+            ";TYPE:Internal infill\n",
+            "G1 X20 Y10 E.5 F3000\n",
+            "G1 X30 Y10 E.5 F9000\n",
+        );
+        let speeds: Config = toml::from_str(
+            r#"
+            [[speeds.InternalInfill]]
+            max_speed = 100
+            accel = 4000
+            accel_to_decel = 2000
+            scv = 5
+
+            [[speeds.InternalInfill]]
+            min_speed = 100
+            accel = 8000
+            accel_to_decel = 4000
+            scv = 5
+            "#,
+        )
+        .unwrap();
+        let mut config = Config {
+            speeds: speeds.speeds,
+            ..CONFIG.clone()
+        };
+        config.features.remove(&FeatureType::InternalInfill);
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+                FeatureType::InternalInfill => ";TYPE:Internal infill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter_map(|line| line.strip_prefix("SET_VELOCITY_LIMIT "))
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "ACCEL=2200 ACCEL_TO_DECEL=1100 SQUARE_CORNER_VELOCITY=5 ; TYPE:External perimeter",
+                "ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                "ACCEL=8000 ACCEL_TO_DECEL=4000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_speed_ranges() {
+        let input = concat!(
+            "M83\n",
+            ";TYPE:Internal infill\n",
+            "G1 X10 Y10 E.5 F3000\n",
+            "G1 X20 Y10 E.5\n",
+            "G1 X30 Y10 E.5 F9000\n",
+            "G1 X40 Y10 E.5 F3000\n",
+        );
+        let speeds: Config = toml::from_str(
+            r#"
+            [[speeds.InternalInfill]]
+            max_speed = 100
+            accel = 4000
+            accel_to_decel = 2000
+            scv = 5
+            "#,
+        )
+        .unwrap();
+        let config = Config {
+            speeds: speeds.speeds,
+            ..CONFIG.clone()
+        };
+
+        let result: String = process(
+            Cursor::new(input.as_bytes()),
+            &config,
+            Dialect::new("Testing", None, |ft: &FeatureType| match ft {
+                FeatureType::InternalInfill => ";TYPE:Internal infill",
+                _ => "TESTING",
+            }),
+        )
+        .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter_map(|line| line.strip_prefix("SET_VELOCITY_LIMIT "))
+            .collect();
+        assert_eq!(
+            control_stmnts,
+            vec![
+                "ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                "ACCEL=6000 ACCEL_TO_DECEL=3000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+                "ACCEL=4000 ACCEL_TO_DECEL=2000 SQUARE_CORNER_VELOCITY=5 ; TYPE:Internal infill",
+            ]
+        );
+    }
 }
@@ -0,0 +1,173 @@
+use crate::slicers::slic3r::{Dialect, LayerChange, Markers};
+use crate::slicers::{slic3r, AccelerationPreProcessor};
+use crate::types::{Config, FeatureType, TravelStyle, Version};
+
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+pub(crate) struct Slic3rProcessor {
+    version: Option<Version>,
+}
+
+impl Slic3rProcessor {
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> = Version::new(1, 3, 0)..Version::new(1, 4, 0);
+
+    /// Slic3r doesn't mark layer changes with a comment
+    pub(crate) const LAYER_CHANGE: LayerChange<'static> = LayerChange::ZHeight;
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in Slic3r
+            FeatureType::FirstLayer => ";TYPE:First layer",
+            FeatureType::SmallPerimeter => ";TYPE:Small perimeter",
+            FeatureType::Travel => ";TYPE:Travel",
+            FeatureType::Custom => ";TYPE:Custom",
+            FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
+            FeatureType::ThinWall => ";TYPE:Thin wall",
+            FeatureType::Brim => ";TYPE:Brim",
+            FeatureType::Ironing => ";TYPE:Ironing",
+            FeatureType::PrimeTower => ";TYPE:Wipe tower",
+            FeatureType::SupportTransition => ";TYPE:Support transition",
+            FeatureType::FloatingVerticalShell => ";TYPE:Floating vertical shell",
+            FeatureType::BottomSurface => ";TYPE:Bottom surface",
+            FeatureType::Wipe => ";WIPE_START",
+            FeatureType::Milling => ";TYPE:Milling",
+
+            // Supported feature types
+            FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+            FeatureType::OverhangPerimeter => ";TYPE:Overhang perimeter",
+            FeatureType::InternalPerimeter => ";TYPE:Perimeter",
+            FeatureType::TopSolidInfill => ";TYPE:Top solid infill",
+            FeatureType::SolidInfill => ";TYPE:Solid infill",
+            FeatureType::InternalInfill => ";TYPE:Internal infill",
+            FeatureType::BridgeInfill => ";TYPE:Bridge infill",
+            FeatureType::GapFill => ";TYPE:Gap fill",
+            FeatureType::Skirt => ";TYPE:Skirt",
+            FeatureType::SupportMaterial => ";TYPE:Support material",
+            FeatureType::SupportMaterialInterface => ";TYPE:Support material interface",
+        }
+    }
+}
+
+impl AccelerationPreProcessor for Slic3rProcessor /**/ {
+    fn process<'a>(
+        &'a self,
+        input: impl Read + Seek + Send + 'a,
+        config: &'a Config,
+    ) -> generator::Generator<'a, (), String> {
+        let dialect = Dialect {
+            version: self.version,
+            slicer: "Slic3r",
+            layer_change: Self::LAYER_CHANGE,
+            travel: TravelStyle::G1,
+            markers: Markers::Builtin(Self::as_marker),
+            wipe_end: None,
+        };
+
+        slic3r::process(input, config, dialect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
+    use std::fs::File;
+
+    #[test]
+    fn test_slic3r_original() {
+        let processor = Slic3rProcessor::new(Some(Version::new(1, 3, 0)));
+        let input = File::open(GCODE_PATH.join("slic3r.gcode")).unwrap();
+
+        let result: String = processor.process(input, &CONFIG).collect();
+        let result: Vec<&str> = result.split('\n').collect();
+        let control_stmnts: Vec<&str> = result
+            .iter()
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:First Layer"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Travel"))
+                .count(),
+            34
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            0
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:External perimeter"))
+                .count(),
+            3
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Solid infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Top solid infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Internal infill"))
+                .count(),
+            8
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Gap fill"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Support material interface"))
+                .count(),
+            1
+        );
+    }
+}
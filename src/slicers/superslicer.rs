@@ -1,13 +1,52 @@
+use crate::slicers::slic3r::Dialect;
 use crate::slicers::{slic3r, AccelerationPreProcessor};
-use crate::types::{AccelerationSettings, FeatureType};
+use crate::types::{Config, FeatureType, Version};
 
 use std::io::{Read, Seek};
+use std::ops::Range;
 
-pub(crate) struct SuperSlicerProcessor {}
+pub(crate) struct SuperSlicerProcessor {
+    version: Option<Version>,
+}
 
 impl SuperSlicerProcessor {
-    pub fn new() -> Self {
-        Self {}
+    /// Versions the marker table has been tested with
+    pub(crate) const TESTED_VERSIONS: Range<Version> = Version::new(2, 2, 0)..Version::new(2, 6, 0);
+
+    pub fn new(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    pub(crate) fn as_marker(feature_type: &FeatureType) -> &str {
+        match feature_type {
+            // Not implemented in Slic3r/PrusaSlicer/SuperSlicer
+            FeatureType::FirstLayer => ";TYPE:First layer",
+            FeatureType::SmallPerimeter => ";TYPE:Small perimeter",
+            FeatureType::Travel => ";TYPE:Travel",
+            FeatureType::Custom => ";TYPE:Custom",
+            FeatureType::SupportTransition => ";TYPE:Support transition",
+            FeatureType::FloatingVerticalShell => ";TYPE:Floating vertical shell",
+            FeatureType::BottomSurface => ";TYPE:Bottom surface",
+            // Supported feature types
+            FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
+            FeatureType::OverhangPerimeter => ";TYPE:Overhang perimeter",
+            FeatureType::InternalPerimeter => ";TYPE:Internal perimeter",
+            FeatureType::TopSolidInfill => ";TYPE:Top solid infill",
+            FeatureType::SolidInfill => ";TYPE:Solid infill",
+            FeatureType::InternalInfill => ";TYPE:Internal infill",
+            FeatureType::BridgeInfill => ";TYPE:Bridge infill",
+            FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
+            FeatureType::ThinWall => ";TYPE:Thin wall",
+            FeatureType::GapFill => ";TYPE:Gap fill",
+            FeatureType::Skirt => ";TYPE:Skirt",
+            FeatureType::SupportMaterial => ";TYPE:Support material",
+            FeatureType::SupportMaterialInterface => ";TYPE:Support material interface",
+            FeatureType::Brim => ";TYPE:Brim",
+            FeatureType::Ironing => ";TYPE:Ironing",
+            FeatureType::PrimeTower => ";TYPE:Wipe tower",
+            FeatureType::Milling => ";TYPE:Milling",
+            FeatureType::Wipe => ";WIPE_START",
+        }
     }
 }
 
@@ -15,47 +54,28 @@ impl AccelerationPreProcessor for SuperSlicerProcessor /**/ {
     fn process<'a>(
         &'a self,
         input: impl Read + Seek + Send + 'a,
-        settings: &'a AccelerationSettings,
+        config: &'a Config,
     ) -> generator::Generator<'a, (), String> {
-        let as_marker: fn(&FeatureType) -> &str = |feature_type: &FeatureType| {
-            match feature_type {
-                // Not implemented in Slic3r/PrusaSlicer/SuperSlicer
-                FeatureType::FirstLayer => ";TYPE:First layer",
-                FeatureType::Travel => ";TYPE:Travel",
-                FeatureType::Custom => ";TYPE:Custom",
-                // Supported feature types
-                FeatureType::ExternalPerimeter => ";TYPE:External perimeter",
-                FeatureType::OverhangPerimeter => ";TYPE:Overhang perimeter",
-                FeatureType::InternalPerimeter => ";TYPE:Internal perimeter",
-                FeatureType::TopSolidInfill => ";TYPE:Top solid infill",
-                FeatureType::SolidInfill => ";TYPE:Solid infill",
-                FeatureType::InternalInfill => ";TYPE:Internal infill",
-                FeatureType::BridgeInfill => ";TYPE:Bridge infill",
-                FeatureType::InternalBridgeInfill => ";TYPE:Internal bridge infill",
-                FeatureType::ThinWall => ";TYPE:Thin wall",
-                FeatureType::GapFill => ";TYPE:Gap fill",
-                FeatureType::Skirt => ";TYPE:Skirt",
-                FeatureType::SupportMaterial => ";TYPE:Support material",
-                FeatureType::SupportMaterialInterface => ";TYPE:Support material interface",
-            }
-        };
-
-        slic3r::process(input, settings, as_marker)
+        slic3r::process(
+            input,
+            config,
+            Dialect::new("SuperSlicer", self.version, Self::as_marker),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::slicers::tests::{GCODE_PATH, SETTINGS};
+    use crate::slicers::tests::{CONFIG, GCODE_PATH};
     use std::fs::File;
 
     #[test]
     fn test_superslicer() {
-        let processor = SuperSlicerProcessor::new();
+        let processor = SuperSlicerProcessor::new(Some(Version::new(2, 3, 56)));
         let input = File::open(GCODE_PATH.join("superslicer.gcode")).unwrap();
 
-        let result: String = processor.process(input, &SETTINGS).collect();
+        let result: String = processor.process(input, &CONFIG).collect();
         let result: Vec<&str> = result.split('\n').collect();
         let control_stmnts: Vec<&str> = result
             .iter()
@@ -75,35 +95,35 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Top solid infill"))
                 .count(),
-            7
+            4
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Travel"))
                 .count(),
-            510
+            504
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Solid infill"))
                 .count(),
-            79
+            45
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Internal infill"))
                 .count(),
-            161
+            97
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Gap fill"))
                 .count(),
-            25
+            18
         );
         assert_eq!(
             control_stmnts
@@ -117,7 +137,7 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Internal bridge infill"))
                 .count(),
-            8
+            4
         );
         assert_eq!(
             control_stmnts
@@ -131,14 +151,64 @@ mod tests {
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:External perimeter"))
                 .count(),
-            127
+            115
         );
         assert_eq!(
             control_stmnts
                 .iter()
                 .filter(|l| l.ends_with("; TYPE:Skirt"))
                 .count(),
-            6
+            5
+        );
+    }
+
+    #[test]
+    fn test_superslicer_roles() {
+        let processor = SuperSlicerProcessor::new(Some(Version::new(2, 5, 59)));
+        let input = "M83\n;TYPE:Skirt\nG1 X10 Y10 E.5\n;TYPE:Brim\nG1 X15 Y15 E.5\n;TYPE:Wipe tower\nG1 X20 Y20 E.5\n;TYPE:Ironing\nG1 X30 Y30 E.05\n;TYPE:Milling\nG1 Z.1\nG1 X40 Y40\n";
+
+        let result: String = processor
+            .process(std::io::Cursor::new(input.as_bytes()), &CONFIG)
+            .collect();
+        let control_stmnts: Vec<&str> = result
+            .split('\n')
+            .filter(|line| line.starts_with("SET_VELOCITY_LIMIT"))
+            .collect();
+
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Skirt"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Brim"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Prime tower"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Ironing"))
+                .count(),
+            1
+        );
+        assert_eq!(
+            control_stmnts
+                .iter()
+                .filter(|l| l.ends_with("; TYPE:Milling"))
+                .count(),
+            1
         );
     }
 }
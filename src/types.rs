@@ -1,43 +1,564 @@
-use serde::Deserialize;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
-use std::fmt::{Debug, Display, Formatter};
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::ops::Range;
 use strum::EnumString;
 pub(crate) type AccelerationSettings = HashMap<FeatureType, AccelerationControl>;
 
 pub(crate) static DEFAULT_TRAVEL_ACCELERATION: AccelerationControl = AccelerationControl {
     accel: 4000,
-    accel_to_decel: 2000,
+    accel_to_decel: Some(2000),
+    minimum_cruise_ratio: None,
     scv: 5,
+    velocity: None,
 };
 pub(crate) static DEFAULT_FIRST_LAYER_ACCELERATION: AccelerationControl = AccelerationControl {
     accel: 2000,
-    accel_to_decel: 1000,
+    accel_to_decel: Some(1000),
+    minimum_cruise_ratio: None,
     scv: 5,
+    velocity: None,
 };
 
-#[derive(Copy, Clone, Deserialize)]
+/// Contents of the configuration file
+#[derive(Clone, Default, Deserialize)]
+pub(crate) struct Config {
+    /// User-defined slicer dialects
+    #[serde(default)]
+    pub(crate) dialects: Vec<DialectConfig>,
+    /// Handling of the first layers of a print
+    #[serde(default)]
+    pub(crate) first_layer: FirstLayerConfig,
+    /// Handling of travel moves
+    #[serde(default)]
+    pub(crate) travel: TravelConfig,
+    /// Detection of small perimeter loops
+    #[serde(default)]
+    pub(crate) small_perimeter: SmallPerimeterConfig,
+    /// Klipper version the commands are generated for
+    #[serde(default)]
+    pub(crate) klipper: KlipperConfig,
+    /// Marlin commands are generated instead of Klipper commands if set
+    #[serde(default)]
+    pub(crate) marlin: Option<MarlinConfig>,
+    /// Acceleration control settings per feature type depending on the print speed
+    #[serde(default)]
+    pub(crate) speeds: HashMap<FeatureType, Vec<SpeedRange>>,
+    /// Acceleration control settings per feature type
+    #[serde(flatten)]
+    pub(crate) features: AccelerationSettings,
+}
+
+impl Config {
+    /// Checks if any feature caps the velocity, Marlin ignores velocity caps
+    pub(crate) fn has_velocity_caps(&self) -> bool {
+        self.marlin.is_none()
+            && self
+                .features
+                .values()
+                .chain(self.speeds.values().flatten().map(|range| &range.control))
+                .any(|control| control.velocity.is_some())
+    }
+
+    /// Firmware the commands are generated for
+    pub(crate) fn firmware(&self) -> Firmware {
+        match self.marlin {
+            Some(marlin) => Firmware::Marlin(marlin),
+            None => Firmware::Klipper(self.klipper),
+        }
+    }
+
+    /// Checks if there are fixed settings or speed ranges for a feature type
+    pub(crate) fn has_settings(&self, feature_type: &FeatureType) -> bool {
+        self.features.contains_key(feature_type) || self.speeds.contains_key(feature_type)
+    }
+
+    /// Acceleration control for a feature printed on a layer at a speed in mm/s, the first
+    /// layer settings take precedence on the first layers. Speeds outside the speed ranges of
+    /// the feature use its fixed settings, features without fixed settings have no control
+    /// at these speeds. Returns the feature type to report the control as.
+    pub(crate) fn control(
+        &self,
+        feature_type: &FeatureType,
+        layer: u64,
+        speed: Option<f64>,
+    ) -> Option<(FeatureType, AccelerationControl)> {
+        let control = speed
+            .and_then(|speed| {
+                self.speeds
+                    .get(feature_type)?
+                    .iter()
+                    .find(|range| range.contains(speed))
+            })
+            .map(|range| range.control)
+            .or_else(|| self.features.get(feature_type).copied())?;
+        if !(1..=self.first_layer.layers).contains(&layer) {
+            return Some((*feature_type, control));
+        }
+
+        let first_layer = *self
+            .features
+            .get(&FeatureType::FirstLayer)
+            .unwrap_or(&DEFAULT_FIRST_LAYER_ACCELERATION);
+        Some(match self.first_layer.mode {
+            FirstLayerMode::Replace => (FeatureType::FirstLayer, first_layer),
+            FirstLayerMode::Cap => (*feature_type, control.min(&first_layer)),
+            FirstLayerMode::Ramp => {
+                let t = (layer - 1) as f64 / self.first_layer.layers as f64;
+                (*feature_type, first_layer.lerp(&control, t))
+            }
+        })
+    }
+}
+
+/// Acceleration control for print moves within a range of speeds
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub(crate) struct SpeedRange {
+    /// Lowest speed in mm/s the settings apply to
+    #[serde(default)]
+    pub(crate) min_speed: f64,
+    /// Speed in mm/s the settings apply up to, not including the speed itself
+    #[serde(default = "unbounded")]
+    pub(crate) max_speed: f64,
+    /// Settings used for print moves within the range
+    #[serde(flatten)]
+    pub(crate) control: AccelerationControl,
+}
+
+impl SpeedRange {
+    /// Checks if a speed in mm/s is within the range
+    pub(crate) fn contains(&self, speed: f64) -> bool {
+        (self.min_speed..self.max_speed).contains(&speed)
+    }
+}
+
+fn unbounded() -> f64 {
+    f64::INFINITY
+}
+
+/// Firmware the acceleration control commands are generated for
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Firmware {
+    /// `SET_VELOCITY_LIMIT` commands
+    Klipper(KlipperConfig),
+    /// `M204` and `M205` commands
+    Marlin(MarlinConfig),
+}
+
+impl Default for Firmware {
+    fn default() -> Self {
+        Firmware::Klipper(KlipperConfig::default())
+    }
+}
+
+/// Firmware setting written by a command, a command is only needed if it changes the value
+/// the setting already has
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Register {
+    /// Klipper velocity limits, all set by a single `SET_VELOCITY_LIMIT` command
+    VelocityLimit,
+    /// Marlin print acceleration set by `M204 P`
+    PrintAccel,
+    /// Marlin travel acceleration set by `M204 T`
+    TravelAccel,
+    /// Marlin junction deviation or jerk set by `M205`
+    Jerk,
+}
+
+impl Firmware {
+    /// Commands setting the acceleration control for a feature type and the firmware setting
+    /// each of them writes
+    pub(crate) fn commands(
+        &self,
+        feature_type: &FeatureType,
+        control: &AccelerationControl,
+    ) -> Vec<(Register, String)> {
+        match self {
+            Firmware::Klipper(klipper) => vec![(Register::VelocityLimit, control.command(klipper))],
+            Firmware::Marlin(marlin) => {
+                control.marlin_commands(*feature_type == FeatureType::Travel, marlin)
+            }
+        }
+    }
+}
+
+/// Settings for generating Marlin commands
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct MarlinConfig {
+    /// How the square corner velocity is applied
+    pub(crate) jerk: JerkMode,
+    /// Retract acceleration, left unchanged if not set
+    pub(crate) retract_accel: Option<usize>,
+}
+
+/// Marlin setting the square corner velocity is applied as
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum JerkMode {
+    /// `M205 J` with the junction deviation converted from the square corner velocity
+    #[default]
+    JunctionDeviation,
+    /// `M205 X Y` with the square corner velocity as classic jerk
+    Classic,
+}
+
+/// How the first layer settings are combined with the feature settings
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FirstLayerMode {
+    /// First layer values are used for all features
+    #[default]
+    Replace,
+    /// Feature values are limited to the first layer values
+    Cap,
+    /// Values ramp linearly from the first layer values to the feature values
+    Ramp,
+}
+
+/// Settings applied to the first layers of a print
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct FirstLayerConfig {
+    /// Number of layers the first layer settings apply to
+    pub(crate) layers: u64,
+    /// How the first layer settings are combined with the feature settings
+    pub(crate) mode: FirstLayerMode,
+}
+
+impl Default for FirstLayerConfig {
+    fn default() -> Self {
+        Self {
+            layers: 1,
+            mode: FirstLayerMode::default(),
+        }
+    }
+}
+
+/// Settings applied to travel moves
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct TravelConfig {
+    /// Travel moves shorter than this distance in mm keep the print acceleration
+    pub(crate) min_distance: f64,
+}
+
+/// Klipper version and printer limits the commands are generated for
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct KlipperConfig {
+    /// Version of Klipper on the printer, commands for older releases are generated if not set
+    #[serde(deserialize_with = "deserialize_version")]
+    pub(crate) version: Option<Version>,
+    /// Maximum velocity of the printer in mm/s, restored for features without a velocity cap.
+    /// Required if any feature has a velocity cap.
+    pub(crate) max_velocity: Option<usize>,
+}
+
+impl KlipperConfig {
+    /// First release replacing `ACCEL_TO_DECEL` with `MINIMUM_CRUISE_RATIO`
+    const MINIMUM_CRUISE_RATIO_VERSION: Version = Version::new(0, 13, 0);
+
+    /// Checks if `SET_VELOCITY_LIMIT` takes `MINIMUM_CRUISE_RATIO` instead of `ACCEL_TO_DECEL`
+    pub(crate) fn has_minimum_cruise_ratio(&self) -> bool {
+        self.version
+            .is_some_and(|version| version >= Self::MINIMUM_CRUISE_RATIO_VERSION)
+    }
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Version>, D::Error> {
+    let version = String::deserialize(deserializer)?;
+    Version::find(version.trim_start_matches('v'))
+        .map(Some)
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid version: {version}")))
+}
+
+/// Settings for detecting small perimeters, e.g. around tiny holes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct SmallPerimeterConfig {
+    /// Perimeter loops shorter than this length in mm use the `SmallPerimeter` settings,
+    /// 0 disables the detection
+    pub(crate) max_length: f64,
+}
+
+/// G-Code command style a slicer uses for travel moves
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+pub(crate) enum TravelStyle {
+    /// `G0` and `G1` moves in the XY plane without extrusion
+    #[default]
+    G1,
+    /// All `G0` moves
+    G0,
+}
+
+/// User-defined slicer dialect processed like the built-in slic3r forks
+#[derive(Clone, Deserialize)]
+pub(crate) struct DialectConfig {
+    /// Name of the slicer, used for reporting
+    pub(crate) name: String,
+    /// Pattern matching the header line that identifies the slicer
+    #[serde(deserialize_with = "deserialize_regex")]
+    pub(crate) header: Regex,
+    /// Comment marking the start of a layer, layers are detected by Z height if not set
+    pub(crate) layer_change: Option<String>,
+    /// G-Code command style used for travel moves
+    #[serde(default)]
+    pub(crate) travel: TravelStyle,
+    /// Comments starting a block of print moves and their feature type
+    pub(crate) markers: HashMap<String, FeatureType>,
+    /// Comment marking the end of a block of wipe moves started by a `Wipe` marker
+    pub(crate) wipe_end: Option<String>,
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(serde::de::Error::custom)
+}
+
+/// Slicer version as found in the G-Code header
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+static VERSION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^(?<major>\d+)\.(?<minor>\d+)(\.(?<patch>\d+))?"#).unwrap());
+
+impl Version {
+    pub(crate) const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Finds the first version number in a line, e.g. `2.6.1` in `; generated by PrusaSlicer 2.6.1 on ...`
+    pub(crate) fn find(line: &str) -> Option<Self> {
+        line.split_whitespace().find_map(|word| {
+            let captures = VERSION_REGEX.captures(word)?;
+            let component = |name: &str| {
+                captures
+                    .name(name)
+                    .map_or(Some(0), |m| m.as_str().parse().ok())
+            };
+            Some(Self::new(
+                component("major")?,
+                component("minor")?,
+                component("patch")?,
+            ))
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Deserialize)]
 pub(crate) struct AccelerationControl {
     /// Acceleration
+    #[serde(deserialize_with = "deserialize_accel")]
     pub(crate) accel: usize,
-    /// Accel to Decel
-    pub(crate) accel_to_decel: usize,
+    /// Accel to Decel, deprecated in favor of the minimum cruise ratio
+    #[serde(default)]
+    pub(crate) accel_to_decel: Option<usize>,
+    /// Minimum Cruise Ratio
+    #[serde(default, deserialize_with = "deserialize_ratio")]
+    pub(crate) minimum_cruise_ratio: Option<f64>,
     /// Square Corner Velocity
     pub(crate) scv: usize,
+    /// Maximum Velocity, the printer's limit applies if not set
+    #[serde(default)]
+    pub(crate) velocity: Option<usize>,
 }
 
-impl Display for AccelerationControl {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f,"SET_VELOCITY_LIMIT ACCEL={accel} ACCEL_TO_DECEL={accel_to_decel} SQUARE_CORNER_VELOCITY={scv}", accel=self.accel, accel_to_decel=self.accel_to_decel, scv=self.scv )
+impl AccelerationControl {
+    /// Valid minimum cruise ratios, Klipper rejects a ratio of 1 or more
+    pub(crate) const MINIMUM_CRUISE_RATIO_RANGE: Range<f64> = 0.0..1.0;
+
+    /// Klipper's default minimum cruise ratio, accel to decel defaults to the same half of
+    /// the acceleration
+    const DEFAULT_MINIMUM_CRUISE_RATIO: f64 = 0.5;
+
+    /// Accel to decel, converted from the minimum cruise ratio if only that is set
+    pub(crate) fn as_accel_to_decel(&self) -> Option<usize> {
+        self.accel_to_decel.or_else(|| {
+            self.minimum_cruise_ratio
+                .map(|ratio| (self.accel as f64 * (1.0 - ratio)).round() as usize)
+        })
+    }
+
+    /// Minimum cruise ratio, converted from accel to decel if only that is set
+    pub(crate) fn as_minimum_cruise_ratio(&self) -> Option<f64> {
+        self.minimum_cruise_ratio.or_else(|| {
+            self.accel_to_decel
+                .map(|accel_to_decel| (1.0 - accel_to_decel as f64 / self.accel as f64).max(0.0))
+        })
+    }
+
+    /// Lowest value of each setting, the highest minimum cruise ratio
+    fn min(&self, other: &Self) -> Self {
+        let (accel_to_decel, minimum_cruise_ratio) = if self.uses_cruise_ratio(other) {
+            (
+                None,
+                combine(
+                    self.as_minimum_cruise_ratio(),
+                    other.as_minimum_cruise_ratio(),
+                    f64::max,
+                ),
+            )
+        } else {
+            (
+                combine(self.accel_to_decel, other.accel_to_decel, usize::min),
+                None,
+            )
+        };
+        Self {
+            accel: self.accel.min(other.accel),
+            accel_to_decel,
+            minimum_cruise_ratio,
+            scv: self.scv.min(other.scv),
+            velocity: combine(self.velocity, other.velocity, usize::min),
+        }
+    }
+
+    /// Linear interpolation towards another control, `t` ranges from 0.0 to 1.0. Optional
+    /// settings set on only one of the controls are used as is.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: usize, b: usize| (a as f64 + (b as f64 - a as f64) * t).round() as usize;
+        let (accel_to_decel, minimum_cruise_ratio) = if self.uses_cruise_ratio(other) {
+            (
+                None,
+                combine(
+                    self.as_minimum_cruise_ratio(),
+                    other.as_minimum_cruise_ratio(),
+                    |a, b| a + (b - a) * t,
+                ),
+            )
+        } else {
+            (
+                combine(self.accel_to_decel, other.accel_to_decel, lerp),
+                None,
+            )
+        };
+        Self {
+            accel: lerp(self.accel, other.accel),
+            accel_to_decel,
+            minimum_cruise_ratio,
+            scv: lerp(self.scv, other.scv),
+            velocity: combine(self.velocity, other.velocity, lerp),
+        }
+    }
+
+    /// Checks if either control sets the minimum cruise ratio, combined controls use it then
+    fn uses_cruise_ratio(&self, other: &Self) -> bool {
+        self.minimum_cruise_ratio.is_some() || other.minimum_cruise_ratio.is_some()
+    }
+
+    /// `SET_VELOCITY_LIMIT` command for the targeted Klipper version, accel to decel and the
+    /// minimum cruise ratio are converted into each other as needed
+    pub(crate) fn command(&self, klipper: &KlipperConfig) -> String {
+        let mut command = format!("SET_VELOCITY_LIMIT ACCEL={}", self.accel);
+        // Klipper keeps the previous value if neither is set, so the default is written instead
+        if klipper.has_minimum_cruise_ratio() {
+            let ratio = self
+                .as_minimum_cruise_ratio()
+                .unwrap_or(Self::DEFAULT_MINIMUM_CRUISE_RATIO);
+            let ratio = (ratio * 100.0).round() / 100.0;
+            let _ = write!(command, " MINIMUM_CRUISE_RATIO={ratio}");
+        } else {
+            let accel_to_decel = self.as_accel_to_decel().unwrap_or_else(|| {
+                (self.accel as f64 * (1.0 - Self::DEFAULT_MINIMUM_CRUISE_RATIO)).round() as usize
+            });
+            let _ = write!(command, " ACCEL_TO_DECEL={accel_to_decel}");
+        }
+        let _ = write!(command, " SQUARE_CORNER_VELOCITY={}", self.scv);
+        // Klipper keeps the velocity of the last command that set one
+        if let Some(velocity) = self.velocity.or(klipper.max_velocity) {
+            let _ = write!(command, " VELOCITY={velocity}");
+        }
+        command
+    }
+
+    /// `M204` and `M205` commands, travel moves set the travel acceleration and all other
+    /// features the print acceleration. Accel to decel and velocity have no equivalent.
+    pub(crate) fn marlin_commands(
+        &self,
+        travel: bool,
+        marlin: &MarlinConfig,
+    ) -> Vec<(Register, String)> {
+        let (register, mut accel) = match travel {
+            true => (Register::TravelAccel, format!("M204 T{}", self.accel)),
+            false => (Register::PrintAccel, format!("M204 P{}", self.accel)),
+        };
+        if let Some(retract_accel) = marlin.retract_accel {
+            let _ = write!(accel, " R{retract_accel}");
+        }
+
+        let jerk = match marlin.jerk {
+            // Same relation between junction deviation and square corner velocity as Klipper
+            JerkMode::JunctionDeviation => {
+                let junction_deviation =
+                    (self.scv as f64).powi(2) * (2f64.sqrt() - 1.0) / self.accel as f64;
+                format!("M205 J{}", (junction_deviation * 10000.0).round() / 10000.0)
+            }
+            JerkMode::Classic => format!("M205 X{scv} Y{scv}", scv = self.scv),
+        };
+
+        vec![(register, accel), (Register::Jerk, jerk)]
+    }
+}
+
+fn deserialize_accel<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    match usize::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::custom("invalid acceleration: 0")),
+        accel => Ok(accel),
+    }
+}
+
+fn deserialize_ratio<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let ratio = f64::deserialize(deserializer)?;
+    if !AccelerationControl::MINIMUM_CRUISE_RATIO_RANGE.contains(&ratio) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid minimum cruise ratio: {ratio}, expected at least 0 and below 1"
+        )));
+    }
+    Ok(Some(ratio))
+}
+
+/// Combines two optional settings, a setting present on only one side is used as is
+fn combine<T>(a: Option<T>, b: Option<T>, f: impl FnOnce(T, T) -> T) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, b) => a.or(b),
     }
 }
 
 impl Debug for AccelerationControl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("AccelerationControl")
-            .field("ACCEL", &self.accel)
-            .field("ACCEL_TO_DECEL", &self.accel_to_decel)
-            .field("SQUARE_CORNER_VELOCITY", &self.scv)
-            .finish()
+        let mut s = f.debug_struct("AccelerationControl");
+        s.field("ACCEL", &self.accel);
+        if let Some(accel_to_decel) = self.accel_to_decel {
+            s.field("ACCEL_TO_DECEL", &accel_to_decel);
+        }
+        if let Some(ratio) = self.minimum_cruise_ratio {
+            s.field("MINIMUM_CRUISE_RATIO", &ratio);
+        }
+        s.field("SQUARE_CORNER_VELOCITY", &self.scv);
+        if let Some(velocity) = self.velocity {
+            s.field("VELOCITY", &velocity);
+        }
+        s.finish()
     }
 }
 
@@ -46,9 +567,22 @@ pub(crate) enum AccelerationType {
     None,
     Print,
     Travel,
+    /// Wipe acceleration is still active after the end of a wipe
+    Wipe,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, EnumString, strum::Display, Deserialize)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    strum::Display,
+    strum::EnumIter,
+    Deserialize,
+)]
 #[strum(ascii_case_insensitive)]
 pub(crate) enum FeatureType {
     #[strum(serialize = "TYPE:First Layer", serialize = "First Layer")]
@@ -83,4 +617,40 @@ pub(crate) enum FeatureType {
     SupportMaterialInterface,
     #[strum(serialize = "TYPE:Custom")]
     Custom,
+    #[strum(serialize = "TYPE:Brim")]
+    Brim,
+    #[strum(serialize = "TYPE:Ironing")]
+    Ironing,
+    #[strum(serialize = "TYPE:Prime tower")]
+    PrimeTower,
+    #[strum(serialize = "TYPE:Support transition")]
+    SupportTransition,
+    #[strum(serialize = "TYPE:Floating vertical shell")]
+    FloatingVerticalShell,
+    #[strum(serialize = "TYPE:Milling")]
+    Milling,
+    #[strum(serialize = "TYPE:Bottom surface")]
+    BottomSurface,
+    #[strum(serialize = "TYPE:Wipe")]
+    Wipe,
+    #[strum(serialize = "TYPE:Small perimeter", serialize = "Small perimeter")]
+    SmallPerimeter,
+}
+
+impl FeatureType {
+    /// Checks if the feature type is detected from a slicer comment, the first layer is
+    /// detected by counting layers and small perimeters by their length instead.
+    pub(crate) fn has_marker(&self) -> bool {
+        !matches!(self, FeatureType::FirstLayer | FeatureType::SmallPerimeter)
+    }
+
+    /// Checks if the feature type is printed as closed loops
+    pub(crate) fn is_perimeter(&self) -> bool {
+        matches!(
+            self,
+            FeatureType::ExternalPerimeter
+                | FeatureType::InternalPerimeter
+                | FeatureType::OverhangPerimeter
+        )
+    }
 }